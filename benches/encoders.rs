@@ -0,0 +1,73 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use cube_solver::bench::{co_encode, cp_encode, encode_comb, encode_perm, phase1_medge_encode, phase1_medge_encode_opt, phase2_udedge_encode};
+use cube_solver::heuristic::PHASE2_MOVES;
+use cube_solver::rubiks_cube::{eo_encode, CubeSequenceRepr};
+
+// Encoders run once per search node, for every table lookup
+
+fn states() -> Vec<CubeSequenceRepr> {
+    let mut rng = StdRng::seed_from_u64(45);
+    (0..256).map(|_| CubeSequenceRepr::random(&mut rng)).collect()
+}
+
+fn bench_util(c: &mut Criterion) {
+    let cubes = states();
+    c.bench_function("encode_perm/8", |b| b.iter(|| {
+        cubes.iter().map(|cube| encode_perm(black_box(&cube.cp().0))).sum::<usize>()
+    }));
+    c.bench_function("encode_perm/12", |b| b.iter(|| {
+        cubes.iter().map(|cube| encode_perm(black_box(&cube.ep().0))).sum::<usize>()
+    }));
+    let combs: Vec<[u8; 4]> = cubes.iter().map(|cube| {
+        let mut buf = [0u8; 4];
+        let mut j = 0;
+        for (i, &e) in cube.ep().0.iter().enumerate() {
+            if (4..8).contains(&e) {
+                buf[j] = i as u8;
+                j += 1;
+            }
+        }
+        buf
+    }).collect();
+    c.bench_function("encode_comb/12,4", |b| b.iter(|| {
+        combs.iter().map(|comb| encode_comb(black_box(comb), 12)).sum::<usize>()
+    }));
+}
+
+fn bench_cube(c: &mut Criterion) {
+    let cubes = states();
+    let mut group = c.benchmark_group("phase1_medge");
+    group.bench_function("encode", |b| b.iter(|| {
+        cubes.iter().map(|cube| phase1_medge_encode(black_box(cube.ep()))).sum::<usize>()
+    }));
+    group.bench_function("encode_opt", |b| b.iter(|| {
+        cubes.iter().map(|cube| phase1_medge_encode_opt(black_box(cube.ep()))).sum::<usize>()
+    }));
+    group.finish();
+
+    c.bench_function("eo_encode", |b| b.iter(|| {
+        cubes.iter().map(|cube| eo_encode(black_box(cube.eo()))).sum::<usize>()
+    }));
+    c.bench_function("co_encode", |b| b.iter(|| {
+        cubes.iter().map(|cube| co_encode(black_box(cube.co()))).sum::<usize>()
+    }));
+    c.bench_function("cp_encode", |b| b.iter(|| {
+        cubes.iter().map(|cube| cp_encode(black_box(cube.cp()))).sum::<usize>()
+    }));
+    // only phase 2 states are valid input here
+    let mut rng = StdRng::seed_from_u64(46);
+    let phase2: Vec<_> = (0..256).map(|_| {
+        let moves: Vec<_> = (0..30).map(|_| *PHASE2_MOVES.choose(&mut rng).unwrap()).collect();
+        *CubeSequenceRepr::from(moves.as_slice()).ep()
+    }).collect();
+    c.bench_function("phase2_udedge_encode", |b| b.iter(|| {
+        phase2.iter().map(|ep| phase2_udedge_encode(black_box(ep))).sum::<usize>()
+    }));
+}
+
+criterion_group!(benches, bench_util, bench_cube);
+criterion_main!(benches);
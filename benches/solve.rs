@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use cube_solver::heuristic::{init_tables, ALL_MOVES};
+use cube_solver::kociemba::{KociembaSolver, SolverConfig};
+use cube_solver::optimal::OptimalSolver;
+use cube_solver::pocket::{PocketCube, PocketSolver};
+use cube_solver::rubiks_cube::CubeSequenceRepr;
+use cube_solver::solver::by_name;
+use cube_solver::thistlethwaite::ThistlethwaiteSolver;
+
+// Full solves over a fixed corpus of random-state cubes; the seed keeps runs comparable
+
+const SEED: u64 = 2020;
+
+fn corpus(n: usize) -> Vec<CubeSequenceRepr> {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    (0..n).map(|_| CubeSequenceRepr::random(&mut rng)).collect()
+}
+
+fn bench_kociemba(c: &mut Criterion) {
+    init_tables();
+    let mut group = c.benchmark_group("kociemba");
+    group.sample_size(10).measurement_time(Duration::from_secs(20));
+    // the first solution phase 2 comes up with, then searching down to 22 moves
+    for &(max_length, n) in [(30, 20), (22, 5)].iter() {
+        let cubes = corpus(n);
+        let solver = KociembaSolver::with_config(SolverConfig { max_length, timeout: None });
+        group.bench_with_input(BenchmarkId::new(format!("{} cubes", n), max_length), &cubes, |b, cubes| b.iter(|| {
+            cubes.iter().map(|cube| solver.solve_with_own_config(cube).unwrap().moves.len()).sum::<usize>()
+        }));
+    }
+    group.finish();
+}
+
+fn bench_others(c: &mut Criterion) {
+    let cubes = corpus(20);
+    ThistlethwaiteSolver::init_tables();
+    let thistlethwaite = ThistlethwaiteSolver::new();
+    c.bench_function("thistlethwaite/20 cubes", |b| b.iter(|| {
+        cubes.iter().map(|cube| thistlethwaite.solve(cube).moves.len()).sum::<usize>()
+    }));
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let pockets: Vec<_> = (0..100).map(|_| PocketCube::random(&mut rng)).collect();
+    let pocket = PocketSolver::new();
+    c.bench_function("pocket/100 cubes", |b| b.iter(|| {
+        pockets.iter().map(|cube| pocket.solve(cube).len()).sum::<usize>()
+    }));
+}
+
+// the whole-cube solvers as the CLI and server pick them, on scrambles short
+// enough for the optimal solver (random states take it minutes)
+fn bench_by_name(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let cubes: Vec<CubeSequenceRepr> = (0..20)
+        .map(|_| CubeSequenceRepr::from(&(0..12).map(|_| ALL_MOVES[rng.gen_range(0..18)]).collect::<Vec<_>>()))
+        .collect();
+    let config = SolverConfig { max_length: 30, timeout: None };
+    OptimalSolver::init_tables();
+    let mut group = c.benchmark_group("by_name");
+    for name in ["kociemba", "thistlethwaite", "optimal"].iter() {
+        let solver = by_name(name).unwrap();
+        group.bench_function(format!("{}/20 scrambles", name), |b| b.iter(|| {
+            cubes.iter().map(|cube| solver.solve(cube, &config).unwrap().moves.len()).sum::<usize>()
+        }));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_kociemba, bench_others, bench_by_name);
+criterion_main!(benches);
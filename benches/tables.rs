@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use cube_solver::heuristic::*;
+use cube_solver::pocket;
+use cube_solver::thistlethwaite::{g0_table, g1_table, g2_table, g3_corner_table, g3_edge_table};
+
+// Generating each pruning table from scratch, with the builders its lazy_static uses
+
+fn bench_kociemba(c: &mut Criterion) {
+    let mut group = c.benchmark_group("kociemba_tables");
+    group.bench_function("phase1_edgeorient", |b| b.iter(phase1_edgeorient_table));
+    group.bench_function("phase1_cornerorient", |b| b.iter(phase1_cornerorient_table));
+    group.bench_function("phase1_medge", |b| b.iter(phase1_medge_table));
+    group.bench_function("phase2_udedge", |b| b.iter(phase2_udedge_table));
+    group.bench_function("phase2_medge", |b| b.iter(phase2_medge_table));
+    group.bench_function("phase2_cornerperm", |b| b.iter(phase2_cornerperm_table));
+    group.finish();
+}
+
+fn bench_thistlethwaite(c: &mut Criterion) {
+    let mut group = c.benchmark_group("thistlethwaite_tables");
+    group.bench_function("g0", |b| b.iter(g0_table));
+    group.bench_function("g3_corner", |b| b.iter(g3_corner_table));
+    group.bench_function("g3_edge", |b| b.iter(g3_edge_table));
+    // G1 and G2 are a million entries or more each
+    group.sample_size(10).measurement_time(Duration::from_secs(30));
+    group.bench_function("g1", |b| b.iter(g1_table));
+    group.bench_function("g2", |b| b.iter(g2_table));
+    group.finish();
+}
+
+fn bench_pocket(c: &mut Criterion) {
+    // a few seconds per run
+    let mut group = c.benchmark_group("pocket_tables");
+    group.sample_size(10).measurement_time(Duration::from_secs(30));
+    group.bench_function("distance", |b| b.iter(pocket::distance_table));
+    group.finish();
+}
+
+criterion_group!(benches, bench_kociemba, bench_thistlethwaite, bench_pocket);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+/* Links against the cdylib and checks cs_solve_facelets end to end; see the Makefile. */
+#include <stdio.h>
+#include <string.h>
+
+#include "cube_solver.h"
+
+/* the cube after R U */
+static const char *SCRAMBLED = "UUUUUUFFFUBBRRRRRRRRRFFDFFDDDBDDBDDBFFDLLLLLLLLLUBBUBB";
+static const char *SOLVED = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+static int failures = 0;
+
+static void check(int ok, const char *what) {
+    printf("%s: %s\n", ok ? "ok  " : "FAIL", what);
+    if (!ok) {
+        failures++;
+    }
+}
+
+int main(void) {
+    char out[128];
+    int n;
+
+    check(cs_init_tables() == CS_OK, "init tables");
+
+    n = cs_solve_facelets(SCRAMBLED, out, sizeof out, 0, 0);
+    check(n == 2 && strcmp(out, "U' R'") == 0, "solve R U");
+
+    n = cs_solve_facelets(SOLVED, out, sizeof out, 0, 0);
+    check(n == 0 && strcmp(out, "") == 0, "solve solved cube");
+
+    n = cs_solve_facelets(SCRAMBLED, out, 3, 0, 0);
+    check(n == CS_ERR_BUFFER_TOO_SMALL, "small buffer");
+
+    n = cs_solve_facelets("UUU", out, sizeof out, 0, 0);
+    check(n == CS_ERR_INVALID_FACELETS, "invalid facelets");
+
+    n = cs_solve_facelets(NULL, out, sizeof out, 0, 0);
+    check(n == CS_ERR_NULL_POINTER, "null input");
+
+    check(strcmp(cs_error_message(CS_ERR_UNSOLVABLE), "cube is not solvable") == 0, "error message");
+
+    return failures == 0 ? 0 : 1;
+}
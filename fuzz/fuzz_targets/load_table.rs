@@ -0,0 +1,15 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use cube_solver::heuristic::PruneTable;
+use cube_solver::rubiks_cube::EdgeOrient;
+
+// Saved pruning tables: a table that loads saves back to the same bytes
+fuzz_target!(|data: &[u8]| {
+    // whatever size the header claims, so the fuzzer can get past the size check
+    let size = data.get(4..8).map_or(0, |b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize);
+    if let Ok(table) = PruneTable::<EdgeOrient>::from_bytes(data, size) {
+        assert_eq!(table.len(), size);
+        assert_eq!(table.to_bytes(), data);
+    }
+});
@@ -0,0 +1,21 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use cube_solver::color::ColorScheme;
+use cube_solver::rubiks_cube::CubeSequenceRepr;
+
+// Facelet and colour strings: a valid cube prints back as the same string
+fuzz_target!(|data: &[u8]| {
+    let s = match std::str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    if let Ok(cube) = CubeSequenceRepr::from_facelets(s) {
+        assert_eq!(cube.to_facelets(), s.trim());
+        assert!(cube.validate().is_ok());
+    }
+    let scheme = ColorScheme::default();
+    if let Ok(cube) = scheme.from_colors(s) {
+        assert_eq!(scheme.to_colors(&cube), s.trim());
+    }
+});
@@ -0,0 +1,19 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use cube_solver::rubiks_cube::CubeSequenceRepr;
+use cube_solver::sequence::MoveSequence;
+
+// Move notation: anything that parses prints back to something that parses the same
+fuzz_target!(|data: &[u8]| {
+    let s = match std::str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    if let Ok(moves) = s.parse::<MoveSequence>() {
+        let again: MoveSequence = moves.to_string().parse().expect("printed moves don't parse");
+        assert_eq!(again.moves(), moves.moves());
+        let cube = CubeSequenceRepr::from(moves.moves());
+        assert_eq!(CubeSequenceRepr::from(moves.simplify().moves()), cube);
+    }
+});
@@ -0,0 +1,45 @@
+/*
+ * C interface to the cube solver (src/ffi.rs).
+ *
+ * Link against the cdylib built by `cargo build --release`
+ * (target/release/libcube_solver.so, .dylib or .dll).
+ */
+#ifndef CUBE_SOLVER_H
+#define CUBE_SOLVER_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* return codes; successful solves return the solution length instead */
+#define CS_OK                     0
+#define CS_ERR_NULL_POINTER      -1
+#define CS_ERR_INVALID_FACELETS  -2
+#define CS_ERR_UNSOLVABLE        -3
+#define CS_ERR_NO_SOLUTION       -4
+#define CS_ERR_BUFFER_TOO_SMALL  -5
+#define CS_ERR_INTERNAL          -6
+
+/* Builds the pruning tables (a few seconds), which otherwise happens on the
+ * first solve. Safe to call more than once and from any thread. The tables
+ * are kept for the life of the process. */
+int cs_init_tables(void);
+
+/* Solves the cube given as a NUL-terminated 54-character facelet string in
+ * U, R, F, D, L, B order and writes the solution in WCA notation
+ * ("R U2 F'"), NUL-terminated, to `out`, which has room for `len` bytes.
+ * A `max_len` or `timeout_ms` of 0 or less means the default: 22 moves and
+ * no timeout. Returns the number of moves, or one of the CS_ERR_ codes.
+ * Thread safe. */
+int cs_solve_facelets(const char *facelets, char *out, size_t len, int max_len, int timeout_ms);
+
+/* Static description of a return code; never NULL, never to be freed. */
+const char *cs_error_message(int code);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CUBE_SOLVER_H */
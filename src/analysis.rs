@@ -0,0 +1,224 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::rubiks_cube::{CornerPerm, CubeSequenceRepr, EdgePerm};
+
+// Element analysis: order, cycle structure and parity
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+// a cycle of corner positions, with the net twist (0, 1 or 2) picked up along the way
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CornerCycle {
+    pub positions: Vec<u8>,
+    pub twist: u8,
+}
+
+// a cycle of edge positions, with the net flip picked up along the way
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeCycle {
+    pub positions: Vec<u8>,
+    pub flip: bool,
+}
+
+// all non-trivial cycles of a cube state; pieces solved in place are left out,
+// pieces twisted or flipped in place show up as cycles of length 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleStructure {
+    pub corners: Vec<CornerCycle>,
+    pub edges: Vec<EdgeCycle>,
+}
+
+impl CornerCycle {
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    // a twisted cycle has to go round three times before the twist cancels out
+    pub fn order(&self) -> usize {
+        if self.twist == 0 { self.len() } else { self.len() * 3 }
+    }
+}
+
+impl EdgeCycle {
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn order(&self) -> usize {
+        if self.flip { self.len() * 2 } else { self.len() }
+    }
+}
+
+// walk the cycles of a permutation, calling `f` with the positions of each one
+fn for_each_cycle<F: FnMut(&[u8])>(perm: &[u8], mut f: F) {
+    let mut visited = [false; 12];
+    let mut buf = Vec::with_capacity(perm.len());
+    for start in 0..perm.len() {
+        if visited[start] {
+            continue;
+        }
+        buf.clear();
+        let mut i = start;
+        while !visited[i] {
+            visited[i] = true;
+            buf.push(i as u8);
+            i = perm[i] as usize;
+        }
+        f(&buf);
+    }
+}
+
+fn perm_parity(perm: &[u8]) -> Parity {
+    let mut odd = false;
+    for_each_cycle(perm, |c| odd ^= c.len() % 2 == 0);
+    if odd { Parity::Odd } else { Parity::Even }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+impl EdgePerm {
+    pub fn parity(&self) -> Parity {
+        perm_parity(&self.0)
+    }
+}
+
+impl CornerPerm {
+    pub fn parity(&self) -> Parity {
+        perm_parity(&self.0)
+    }
+}
+
+impl CubeSequenceRepr {
+    pub fn cycles(&self) -> CycleStructure {
+        let mut corners = vec![];
+        for_each_cycle(&self.cp.0, |c| {
+            let twist = c.iter().map(|&i| self.co.0[i as usize]).sum::<u8>() % 3;
+            if c.len() > 1 || twist != 0 {
+                corners.push(CornerCycle { positions: c.to_vec(), twist });
+            }
+        });
+        let mut edges = vec![];
+        for_each_cycle(&self.ep.0, |c| {
+            let flip = c.iter().fold(false, |acc, &i| acc ^ self.eo.0[i as usize]);
+            if c.len() > 1 || flip {
+                edges.push(EdgeCycle { positions: c.to_vec(), flip });
+            }
+        });
+        CycleStructure { corners, edges }
+    }
+
+    // number of times the state has to be applied to get back to solved
+    pub fn order(&self) -> usize {
+        let cycles = self.cycles();
+        let corners = cycles.corners.iter().map(CornerCycle::order);
+        let edges = cycles.edges.iter().map(EdgeCycle::order);
+        corners.chain(edges).fold(1, lcm)
+    }
+
+    // permutation parity; corners and edges always agree on a reachable cube
+    pub fn parity(&self) -> Parity {
+        self.cp.parity()
+    }
+}
+
+impl Display for CycleStructure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut parts = vec![];
+        for c in self.corners.iter().filter(|c| c.len() > 1) {
+            let twist = if c.twist != 0 { " (twisted)" } else { "" };
+            parts.push(format!("{}-cycle of corners{}", c.len(), twist));
+        }
+        for c in self.edges.iter().filter(|c| c.len() > 1) {
+            let flip = if c.flip { " (flipped)" } else { "" };
+            parts.push(format!("{}-cycle of edges{}", c.len(), flip));
+        }
+        let twisted = self.corners.iter().filter(|c| c.len() == 1).count();
+        if twisted > 0 {
+            parts.push(format!("{} twisted corner{}", twisted, if twisted > 1 { "s" } else { "" }));
+        }
+        let flipped = self.edges.iter().filter(|c| c.len() == 1).count();
+        if flipped > 0 {
+            parts.push(format!("{} flipped edge{}", flipped, if flipped > 1 { "s" } else { "" }));
+        }
+        if parts.is_empty() {
+            write!(f, "identity")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rubiks_cube::Rotation::*;
+    use crate::rubiks_cube::{CubeSequenceRepr, Rotation};
+
+    fn power(cube: &CubeSequenceRepr, n: usize) -> CubeSequenceRepr {
+        let mut res = CubeSequenceRepr::new();
+        for _ in 0..n {
+            res = res.multiply(cube);
+        }
+        res
+    }
+
+    #[test]
+    fn test_multiply() {
+        let a = [R, U, Rr, Ur, F2];
+        let b = [L, D2, Br, R];
+        let ab: Vec<Rotation> = a.iter().chain(b.iter()).cloned().collect();
+        let composed = CubeSequenceRepr::from(&a).multiply(&CubeSequenceRepr::from(&b));
+        assert_eq!(composed, CubeSequenceRepr::from(&ab));
+    }
+
+    #[test]
+    fn test_order() {
+        assert_eq!(CubeSequenceRepr::new().order(), 1);
+        assert_eq!(CubeSequenceRepr::from(&[U]).order(), 4);
+        assert_eq!(CubeSequenceRepr::from(&[R, U, Rr, Ur]).order(), 6);
+        assert_eq!(CubeSequenceRepr::from(&[R, U]).order(), 105);
+        let cube = CubeSequenceRepr::from(&[R, U2, Dr, B, Dr]);
+        let order = cube.order();
+        assert_eq!(power(&cube, order), CubeSequenceRepr::new());
+        assert_eq!(order, 1260);
+    }
+
+    #[test]
+    fn test_cycles() {
+        assert_eq!(CubeSequenceRepr::new().cycles().to_string(), "identity");
+        // sexy move: four corners moved, one edge 3-cycle
+        let cycles = CubeSequenceRepr::from(&[R, U, Rr, Ur]).cycles();
+        assert_eq!(cycles.edges.len(), 1);
+        assert_eq!(cycles.edges[0].len(), 3);
+        assert_eq!(cycles.corners.iter().map(CornerCycle::len).sum::<usize>(), 4);
+        let cycles = CubeSequenceRepr::from(&[U2]).cycles();
+        assert_eq!(cycles.to_string(), "2-cycle of corners, 2-cycle of corners, 2-cycle of edges, 2-cycle of edges");
+    }
+
+    #[test]
+    fn test_parity() {
+        assert_eq!(CubeSequenceRepr::new().parity(), Parity::Even);
+        assert_eq!(CubeSequenceRepr::from(&[U]).parity(), Parity::Odd);
+        assert_eq!(CubeSequenceRepr::from(&[U2]).parity(), Parity::Even);
+        let cube = CubeSequenceRepr::from(&[R, U, F, Dr, L2]);
+        assert_eq!(cube.cp.parity(), cube.ep.parity());
+    }
+}
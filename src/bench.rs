@@ -0,0 +1,42 @@
+use crate::heuristic;
+use crate::rubiks_cube::{self, CornerOrient, CornerPerm, EdgePerm};
+use crate::util;
+
+// Thin wrappers over the crate-only encoders so the benchmarks can time them;
+// only built with the `bench` feature. Like the encoders, they expect the
+// pieces of a real cube.
+
+#[inline(always)]
+pub fn encode_perm(arr: &[u8]) -> usize {
+    util::encode_perm(arr)
+}
+
+#[inline(always)]
+pub fn encode_comb(arr: &[u8], n: usize) -> usize {
+    util::encode_comb(arr, n)
+}
+
+#[inline(always)]
+pub fn co_encode(repr: &CornerOrient) -> usize {
+    rubiks_cube::co_encode(repr)
+}
+
+#[inline(always)]
+pub fn cp_encode(repr: &CornerPerm) -> usize {
+    rubiks_cube::cp_encode(repr)
+}
+
+#[inline(always)]
+pub fn phase1_medge_encode(repr: &EdgePerm) -> usize {
+    heuristic::phase1_medge_encode(repr)
+}
+
+#[inline(always)]
+pub fn phase1_medge_encode_opt(repr: &EdgePerm) -> usize {
+    heuristic::phase1_medge_encode_opt(repr)
+}
+
+#[inline(always)]
+pub fn phase2_udedge_encode(repr: &EdgePerm) -> usize {
+    heuristic::phase2_udedge_encode(repr)
+}
@@ -0,0 +1,333 @@
+use std::fmt::{Display, Formatter, Result};
+use std::str::FromStr;
+
+use rand::Rng;
+
+use crate::num_traits::{FromPrimitive, ToPrimitive};
+use crate::rubiks_cube::{ColoredCube, CubeRepr, Face, ParseError, Rotation};
+
+// NxNxN cubes (4x4, 5x5, ...), for tracking state rather than solving
+//
+// BigCube keeps every sticker, laid out like ColoredCube: U with B at the top,
+// L F R B in a row, D with F at the top. A turn moves stickers through 3D
+// coordinates doubled so they stay integral: with n = N as i32, cubie centres
+// sit at -(n-1), -(n-3), ..., n-1 on each axis and the stickers of a face at
+// +-n along its axis. x points to R, y to U and z to F.
+
+// A turn of one or more layers, counted from `face`: layer 1 is the face itself
+//
+// `R` is depth 1, `2R` the second layer alone, `Rw` the outer two layers and
+// `3Rw` the outer three.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BigMove {
+    pub face: Face,
+    pub depth: usize,
+    // all layers from the face down to `depth`, rather than just that one
+    pub wide: bool,
+    // clockwise quarter turns: 1, 2 or 3
+    pub turns: u8,
+}
+
+impl BigMove {
+    // turn of the outer layer only, as on a 3x3
+    pub fn outer(r: Rotation) -> Self {
+        BigMove {
+            face: Face::from_u8(r.normal().to_u8().unwrap()).unwrap(),
+            depth: 1,
+            wide: false,
+            turns: r.quarter_turns(),
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        BigMove { turns: 4 - self.turns % 4, ..*self }
+    }
+
+    // where this sends the sticker at row `r`, column `c` of `face` on an
+    // `n`-layer cube; stickers outside the turned layers stay put
+    pub(crate) fn destination(&self, face: Face, r: usize, c: usize, n: usize) -> (Face, usize, usize) {
+        let (axis, sign) = axis(self.face);
+        let n = n as i32;
+        let mut pos = position(face, r, c, n);
+        // stickers on a face count as part of the layer behind them
+        let along = (pos[axis] * sign).clamp(-(n - 1), n - 1);
+        if !self.turns_layer(((n - 1 - along) / 2) as usize) {
+            return (face, r, c);
+        }
+        for _ in 0..self.turns {
+            pos = quarter_turn(pos, axis, sign);
+        }
+        sticker(pos, n)
+    }
+
+    // whether this turns the 0-based `layer`, counting from the face inwards
+    fn turns_layer(&self, layer: usize) -> bool {
+        layer < self.depth && (self.wide || layer + 1 == self.depth)
+    }
+}
+
+impl Display for BigMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        // `Rw` is short for `2Rw`
+        if self.depth > 2 || (self.depth == 2 && !self.wide) {
+            write!(f, "{}", self.depth)?;
+        }
+        write!(f, "{}", self.face.to_char())?;
+        if self.wide {
+            write!(f, "w")?;
+        }
+        let suffix = match self.turns {
+            2 => "2",
+            3 => "'",
+            _ => "",
+        };
+        write!(f, "{}", suffix)
+    }
+}
+
+// `R`, `2R`, `Rw`, `3Rw`, each with `'` or `2` like a 3x3 move; the depth isn't
+// checked against any cube size here, see BigCube::parse_moves
+impl FromStr for BigMove {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || ParseError::InvalidMove(s.to_string());
+        let digits = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let depth = if digits == 0 { None } else { Some(s[..digits].parse::<usize>().map_err(|_| invalid())?) };
+        let mut rest = s[digits..].chars();
+        let face = rest.next().and_then(Face::from_char).ok_or_else(invalid)?;
+        let rest = rest.as_str();
+        let (wide, suffix) = match rest.strip_prefix('w') {
+            Some(suffix) => (true, suffix),
+            None => (false, rest),
+        };
+        let depth = match depth {
+            Some(0) => return Err(invalid()),
+            Some(1) if wide => return Err(invalid()),
+            Some(d) => d,
+            None if wide => 2,
+            None => 1,
+        };
+        let turns = match suffix {
+            "" => 1,
+            "'" | "’" => 3,
+            "2" | "2'" | "2’" => 2,
+            _ => return Err(invalid()),
+        };
+        Ok(BigMove { face, depth, wide, turns })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BigCube<const N: usize> {
+    pub faces: [[[Face; N]; N]; 6],
+}
+
+impl<const N: usize> Default for BigCube<N> {
+    fn default() -> Self {
+        BigCube::new()
+    }
+}
+
+impl<const N: usize> CubeRepr for BigCube<N> {
+    fn rotate(&mut self, r: Rotation) {
+        self.turn(BigMove::outer(r));
+    }
+}
+
+const FACES: [Face; 6] = [Face::U, Face::D, Face::R, Face::L, Face::F, Face::B];
+
+impl<const N: usize> BigCube<N> {
+    pub fn new() -> Self {
+        let mut faces = [[[Face::U; N]; N]; 6];
+        for (i, &f) in FACES.iter().enumerate() {
+            faces[i] = [[f; N]; N];
+        }
+        BigCube { faces }
+    }
+
+    // every face a single colour; without fixed centres (even N) the colours
+    // can end up on any face
+    pub fn is_solved(&self) -> bool {
+        self.faces.iter().all(|face| face.iter().flatten().all(|&f| f == face[0][0]))
+    }
+
+    // layers past the far side of the cube are left alone
+    pub fn turn(&mut self, m: BigMove) {
+        let mut res = self.faces;
+        for (f, face) in self.faces.iter().enumerate() {
+            for (r, row) in face.iter().enumerate() {
+                for (c, &color) in row.iter().enumerate() {
+                    let (to, tr, tc) = m.destination(FACES[f], r, c, N);
+                    res[to.to_usize().unwrap()][tr][tc] = color;
+                }
+            }
+        }
+        self.faces = res;
+    }
+
+    pub fn apply(&mut self, moves: &[BigMove]) {
+        for &m in moves.iter() {
+            self.turn(m);
+        }
+    }
+
+    // moves like `Rw U2 3Fw'`, none deeper than the cube
+    pub fn parse_moves(s: &str) -> std::result::Result<Vec<BigMove>, ParseError> {
+        s.split_whitespace().map(|tok| {
+            let m: BigMove = tok.parse()?;
+            if m.depth > N {
+                return Err(ParseError::InvalidMove(format!("{} on a {}x{}x{} cube", tok, N, N, N)));
+            }
+            Ok(m)
+        }).collect()
+    }
+
+    // random-move scramble of outer and wide turns down to half the cube, as
+    // WCA scrambles for the big cubes are; no two moves in a row on the same axis
+    pub fn scramble<R: Rng + ?Sized>(rng: &mut R, len: usize) -> Vec<BigMove> {
+        let mut res: Vec<BigMove> = Vec::with_capacity(len);
+        let max_depth = (N / 2).max(1);
+        while res.len() < len {
+            let face = FACES[rng.gen_range(0..6)];
+            if res.last().is_some_and(|m| axis(m.face).0 == axis(face).0) {
+                continue;
+            }
+            let depth = rng.gen_range(1..=max_depth);
+            res.push(BigMove { face, depth, wide: depth > 1, turns: rng.gen_range(1..4) });
+        }
+        res
+    }
+
+    pub fn visualize(&self, buf: &mut ColoredCube<N>) {
+        buf.dat = self.faces;
+    }
+}
+
+// axis index (0 = x, 1 = y, 2 = z) and which end of it `face` is on
+fn axis(face: Face) -> (usize, i32) {
+    match face {
+        Face::R => (0, 1),
+        Face::L => (0, -1),
+        Face::U => (1, 1),
+        Face::D => (1, -1),
+        Face::F => (2, 1),
+        Face::B => (2, -1),
+    }
+}
+
+// where the sticker at row `r`, column `c` of `face` is, in doubled coordinates
+pub(crate) fn position(face: Face, r: usize, c: usize, n: i32) -> [i32; 3] {
+    let u = 2 * c as i32 - (n - 1);
+    let v = 2 * r as i32 - (n - 1);
+    match face {
+        Face::U => [u, n, v],
+        Face::D => [u, -n, -v],
+        Face::R => [n, -v, -u],
+        Face::L => [-n, -v, u],
+        Face::F => [u, -v, n],
+        Face::B => [-u, -v, -n],
+    }
+}
+
+// and back
+pub(crate) fn sticker(pos: [i32; 3], n: i32) -> (Face, usize, usize) {
+    let index = |x: i32| ((x + n - 1) / 2) as usize;
+    let [x, y, z] = pos;
+    if y == n {
+        (Face::U, index(z), index(x))
+    } else if y == -n {
+        (Face::D, index(-z), index(x))
+    } else if x == n {
+        (Face::R, index(-y), index(-z))
+    } else if x == -n {
+        (Face::L, index(-y), index(z))
+    } else if z == n {
+        (Face::F, index(-y), index(x))
+    } else {
+        (Face::B, index(-y), index(-x))
+    }
+}
+
+// clockwise as seen from the `sign` end of `axis`
+fn quarter_turn(pos: [i32; 3], axis: usize, sign: i32) -> [i32; 3] {
+    let [x, y, z] = pos;
+    match axis {
+        0 => [x, sign * z, -sign * y],
+        1 => [-sign * z, y, sign * x],
+        _ => [sign * y, -sign * x, z],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::rubiks_cube::CubeSequenceRepr;
+    use crate::rubiks_cube::Rotation::*;
+
+    #[test]
+    fn test_matches_3x3() {
+        let moves = [R, U, Fr, D2, L, B, Ur, R2, Dr, Lr, F2, B2, Br];
+        let mut big = BigCube::<3>::new();
+        for &r in moves.iter() {
+            big.rotate(r);
+        }
+        let (mut expected, mut vis) = (ColoredCube::new(), ColoredCube::new());
+        CubeSequenceRepr::from(&moves).visualize(&mut expected);
+        big.visualize(&mut vis);
+        assert_eq!(vis.dat, expected.dat);
+    }
+
+    #[test]
+    fn test_inner_layers() {
+        let mut wide = BigCube::<5>::new();
+        wide.apply(&BigCube::<5>::parse_moves("3Rw U").unwrap());
+        let mut layers = BigCube::<5>::new();
+        layers.apply(&BigCube::<5>::parse_moves("R 2R 3R U").unwrap());
+        assert_eq!(wide, layers);
+        // the slices of a 4x4 only touch the stickers around them
+        let mut slice = BigCube::<4>::new();
+        slice.apply(&BigCube::<4>::parse_moves("2R").unwrap());
+        assert_eq!(slice.faces[0][0], [Face::U, Face::U, Face::F, Face::U]);
+        assert_eq!(slice.faces[2], BigCube::<4>::new().faces[2]);
+        let m: BigMove = "3Fw'".parse().unwrap();
+        slice.turn(m);
+        slice.turn(m.reverse());
+        assert!(!slice.is_solved());
+        slice.turn("2R'".parse().unwrap());
+        assert!(slice.is_solved());
+    }
+
+    #[test]
+    fn test_parse() {
+        let moves = BigCube::<7>::parse_moves("R 2R' Rw2 3Fw' 3D U’").unwrap();
+        let text: Vec<String> = moves.iter().map(|m| m.to_string()).collect();
+        assert_eq!(text.join(" "), "R 2R' Rw2 3Fw' 3D U'");
+        assert_eq!(moves[2], BigMove { face: Face::R, depth: 2, wide: true, turns: 2 });
+        assert!(BigCube::<4>::parse_moves("5Rw").is_err());
+        for bad in ["", "w", "0R", "1Rw", "Rx", "2", "R3"].iter() {
+            assert!(bad.parse::<BigMove>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_scramble() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let scramble = BigCube::<6>::scramble(&mut rng, 80);
+        assert_eq!(scramble.len(), 80);
+        assert!(scramble.iter().all(|m| m.depth <= 3 && m.wide == (m.depth > 1)));
+        assert!(scramble.windows(2).all(|w| axis(w[0].face).0 != axis(w[1].face).0));
+        let mut cube = BigCube::<6>::new();
+        cube.apply(&scramble);
+        let mut vis = ColoredCube::new();
+        cube.visualize(&mut vis);
+        assert_eq!(vis.to_string().lines().count(), 18);
+        for &f in FACES.iter() {
+            assert_eq!(cube.faces.iter().flatten().flatten().filter(|&&s| s == f).count(), 36);
+        }
+        let undo: Vec<BigMove> = scramble.iter().rev().map(BigMove::reverse).collect();
+        cube.apply(&undo);
+        assert_eq!(cube, BigCube::new());
+    }
+}
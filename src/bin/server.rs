@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::exit;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use cube_solver::heuristic::init_tables;
+use cube_solver::kociemba::{KociembaSolver, SolverConfig};
+use cube_solver::rubiks_cube::{CubeSequenceRepr, ParseError};
+use cube_solver::sequence::MoveSequence;
+use cube_solver::solver::{self, SolveError};
+
+// Local HTTP/JSON solving service
+//
+//   POST /solve     {"facelets": "..."} or {"moves": "..."}, optionally with
+//                   "algorithm", "max_length" and "timeout_ms"
+//   GET  /scramble  random-state scramble, `?seed=N` for a reproducible one
+//   POST /validate  same body as /solve, reports whether the cube is solvable
+
+const USAGE: &str = "\
+Usage: cube-solver-server [options]
+
+Options:
+      --host HOST         address to listen on (default 127.0.0.1)
+  -p, --port PORT         port to listen on (default 8080)
+      --threads N         request handler threads (default: number of CPUs)
+  -l, --max-length N      default solution length to stop at (default 22)
+  -t, --timeout MS        default and maximum per-request timeout (default 5000)
+  -h, --help              print this help";
+
+// requests bigger than this are rejected outright
+const MAX_BODY: u64 = 4096;
+
+struct Config {
+    host: String,
+    port: u16,
+    threads: usize,
+    solver: SolverConfig,
+}
+
+#[derive(Deserialize)]
+struct CubeRequest {
+    facelets: Option<String>,
+    moves: Option<String>,
+    algorithm: Option<String>,
+    max_length: Option<usize>,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct SolveResponse {
+    solution: String,
+    length: usize,
+    phases: Vec<usize>,
+    nodes: u64,
+    time_ms: f64,
+}
+
+#[derive(Serialize)]
+struct ScrambleResponse {
+    scramble: String,
+    facelets: String,
+}
+
+#[derive(Serialize)]
+struct ValidateResponse {
+    valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+// solvers by algorithm name, each built on first request since some take a
+// while to set up their tables; "optimal" builds its 70MB of tables on its first
+// solve instead, just once, and the requests waiting on that count the wait
+// against their timeout
+#[derive(Default)]
+struct Solvers {
+    built: Mutex<HashMap<String, Arc<dyn solver::Solver>>>,
+}
+
+impl Solvers {
+    fn get(&self, name: &str) -> Option<Arc<dyn solver::Solver>> {
+        if let Some(s) = self.built.lock().unwrap().get(name) {
+            return Some(Arc::clone(s));
+        }
+        // built without the lock, so requests for other solvers don't wait on
+        // it; if two requests race to build the same one, the first one kept wins
+        let s: Arc<dyn solver::Solver> = solver::by_name(name)?.into();
+        Some(Arc::clone(self.built.lock().unwrap().entry(name.to_string()).or_insert(s)))
+    }
+}
+
+fn parse_args() -> Result<Config, String> {
+    let mut config = Config {
+        host: "127.0.0.1".to_string(),
+        port: 8080,
+        threads: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        solver: SolverConfig {
+            timeout: Some(Duration::from_millis(5000)),
+            ..SolverConfig::default()
+        },
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            println!("{}", USAGE);
+            exit(0);
+        }
+        let value = args.next().ok_or(format!("option '{}' needs a value", arg))?;
+        let invalid = |_| format!("invalid value '{}' for '{}'", value, arg);
+        match arg.as_str() {
+            "--host" => config.host = value.clone(),
+            "-p" | "--port" => config.port = value.parse().map_err(invalid)?,
+            "--threads" => config.threads = value.parse().map_err(invalid)?,
+            "-l" | "--max-length" => config.solver.max_length = value.parse().map_err(invalid)?,
+            "-t" | "--timeout" => config.solver.timeout = Some(Duration::from_millis(value.parse().map_err(invalid)?)),
+            _ => return Err(format!("unknown option '{}'", arg)),
+        }
+    }
+    Ok(config)
+}
+
+type JsonResponse = Response<std::io::Cursor<Vec<u8>>>;
+
+fn json<T: Serialize>(status: u16, body: &T) -> JsonResponse {
+    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    Response::from_data(serde_json::to_vec(body).unwrap())
+        .with_status_code(status)
+        .with_header(header)
+}
+
+fn error(status: u16, msg: &str) -> JsonResponse {
+    json(status, &ErrorResponse { error: msg.to_string() })
+}
+
+fn read_cube_request(request: &mut Request) -> Result<CubeRequest, JsonResponse> {
+    let too_large = || error(413, &format!("request body over {} bytes", MAX_BODY));
+    if request.body_length().is_some_and(|n| n as u64 > MAX_BODY) {
+        return Err(too_large());
+    }
+    // one byte past the limit is enough to tell the body is too big
+    let mut body = String::new();
+    request.as_reader().take(MAX_BODY + 1).read_to_string(&mut body).map_err(|e| error(400, &e.to_string()))?;
+    if body.len() as u64 > MAX_BODY {
+        return Err(too_large());
+    }
+    serde_json::from_str(&body).map_err(|e| error(400, &e.to_string()))
+}
+
+fn parse_cube(req: &CubeRequest) -> Result<CubeSequenceRepr, String> {
+    match (&req.facelets, &req.moves) {
+        (Some(f), None) => CubeSequenceRepr::from_facelets(f).map_err(|e| e.to_string()),
+        (None, Some(m)) => {
+            let moves: MoveSequence = m.parse().map_err(|e: ParseError| e.to_string())?;
+            Ok(CubeSequenceRepr::from(moves.moves()))
+        }
+        _ => Err("expected exactly one of \"facelets\" or \"moves\"".to_string()),
+    }
+}
+
+fn handle_solve(request: &mut Request, defaults: &SolverConfig, solvers: &Solvers) -> JsonResponse {
+    let req = match read_cube_request(request) {
+        Ok(req) => req,
+        Err(response) => return response,
+    };
+    let cube = match parse_cube(&req) {
+        Ok(cube) => cube,
+        Err(e) => return error(400, &e),
+    };
+    let name = req.algorithm.as_deref().unwrap_or("kociemba");
+    let solver = match solvers.get(name) {
+        Some(solver) => solver,
+        None => return error(400, &format!("unknown algorithm '{}', expected one of: {}", name, solver::ALGORITHMS.join(", "))),
+    };
+    // requests may ask for less time than the server default, never more
+    let mut config = *defaults;
+    if let Some(n) = req.max_length {
+        config.max_length = n;
+    }
+    if let Some(ms) = req.timeout_ms {
+        let requested = Duration::from_millis(ms);
+        config.timeout = Some(config.timeout.map_or(requested, |t| t.min(requested)));
+    }
+    match solver.solve(&cube, &config) {
+        Ok(s) => json(200, &SolveResponse {
+            solution: s.moves.to_string(),
+            length: s.moves.len(),
+            phases: s.phases,
+            nodes: s.nodes,
+            time_ms: s.elapsed.as_secs_f64() * 1000.0,
+        }),
+        Err(e @ SolveError::Timeout) => error(504, &e.to_string()),
+        Err(e) => error(422, &e.to_string()),
+    }
+}
+
+fn handle_scramble(request: &Request, config: &SolverConfig) -> JsonResponse {
+    let seed = request.url().split_once('?').and_then(|(_, query)| {
+        query.split('&').find_map(|kv| kv.strip_prefix("seed=")).and_then(|v| v.parse().ok())
+    });
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let cube = CubeSequenceRepr::random(&mut rng);
+    match KociembaSolver::with_config(*config).solve_with_own_config(&cube) {
+        Some(s) => json(200, &ScrambleResponse {
+            scramble: s.moves.inverse().to_string(),
+            facelets: cube.to_facelets(),
+        }),
+        None => error(504, &SolveError::Timeout.to_string()),
+    }
+}
+
+fn handle_validate(request: &mut Request) -> JsonResponse {
+    let req = match read_cube_request(request) {
+        Ok(req) => req,
+        Err(response) => return response,
+    };
+    let res = match parse_cube(&req) {
+        Ok(_) => ValidateResponse { valid: true, error: None },
+        Err(e) => ValidateResponse { valid: false, error: Some(e) },
+    };
+    json(200, &res)
+}
+
+fn handle(request: &mut Request, config: &SolverConfig, solvers: &Solvers) -> JsonResponse {
+    let path = request.url().split('?').next().unwrap_or("").to_string();
+    match (request.method(), path.as_str()) {
+        (Method::Post, "/solve") => handle_solve(request, config, solvers),
+        (Method::Get, "/scramble") => handle_scramble(request, config),
+        (Method::Post, "/validate") => handle_validate(request),
+        (_, "/solve") | (_, "/scramble") | (_, "/validate") => error(405, "method not allowed"),
+        _ => error(404, "not found"),
+    }
+}
+
+fn serve(server: &Server, config: &SolverConfig, solvers: &Solvers) {
+    for mut request in server.incoming_requests() {
+        let response = handle(&mut request, config, solvers);
+        // the client may have gone away, nothing to do about it
+        let _ = request.respond(response);
+    }
+}
+
+fn main() {
+    let config = match parse_args() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            exit(2);
+        }
+    };
+    // the pruning tables are shared by every request, build them before taking any
+    init_tables();
+    let addr = format!("{}:{}", config.host, config.port);
+    let server = match Server::http(&addr) {
+        Ok(server) => Arc::new(server),
+        Err(e) => {
+            eprintln!("error: can't listen on {}: {}", addr, e);
+            exit(1);
+        }
+    };
+    eprintln!("listening on http://{}", addr);
+    let solver = config.solver;
+    let solvers = Arc::new(Solvers::default());
+    let handlers: Vec<_> = (0..config.threads.max(1)).map(|_| {
+        let server = Arc::clone(&server);
+        let solvers = Arc::clone(&solvers);
+        thread::spawn(move || serve(&server, &solver, &solvers))
+    }).collect();
+    for h in handlers {
+        let _ = h.join();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::Value;
+    use tiny_http::TestRequest;
+
+    fn send(request: TestRequest) -> (u16, Value) {
+        let mut request: Request = request.into();
+        let config = SolverConfig::default();
+        let response = handle(&mut request, &config, &Solvers::default());
+        let status = response.status_code().0;
+        (status, serde_json::from_reader(response.into_reader()).unwrap())
+    }
+
+    fn post(path: &str, body: &'static str) -> (u16, Value) {
+        send(TestRequest::new().with_method(Method::Post).with_path(path).with_body(body))
+    }
+
+    #[test]
+    fn test_solve() {
+        let (status, body) = post("/solve", r#"{"moves": "R U F' D2", "algorithm": "thistlethwaite"}"#);
+        assert_eq!(status, 200);
+        let scramble: MoveSequence = "R U F' D2".parse().unwrap();
+        let solution: MoveSequence = body["solution"].as_str().unwrap().parse().unwrap();
+        let cube = CubeSequenceRepr::from(scramble.moves()).multiply(&CubeSequenceRepr::from(solution.moves()));
+        assert_eq!(cube, CubeSequenceRepr::new());
+        assert_eq!(body["length"], solution.len());
+
+        let (status, body) = post("/solve", r#"{"facelets": "UUU"}"#);
+        assert_eq!(status, 400);
+        assert!(body["error"].is_string());
+        let (status, body) = post("/solve", r#"{"moves": "R", "algorithm": "nope"}"#);
+        assert_eq!(status, 400);
+        assert!(body["error"].as_str().unwrap().contains("kociemba"));
+        assert_eq!(post("/solve", r#"{"moves": "R", "facelets": "x"}"#).0, 400);
+        assert_eq!(post("/solve", "not json").0, 400);
+    }
+
+    #[test]
+    fn test_body_limit() {
+        let padded = format!(r#"{{"moves": "R{}"}}"#, " ".repeat(MAX_BODY as usize - 14));
+        assert_eq!(padded.len() as u64, MAX_BODY);
+        assert_eq!(post("/validate", Box::leak(padded.into_boxed_str())).0, 200);
+        let (status, body) = post("/validate", Box::leak(" ".repeat(MAX_BODY as usize + 1).into_boxed_str()));
+        assert_eq!(status, 413);
+        assert!(body["error"].is_string());
+    }
+
+    #[test]
+    fn test_scramble() {
+        let get = |path| send(TestRequest::new().with_path(path));
+        let (status, body) = get("/scramble?seed=7");
+        assert_eq!(status, 200);
+        assert_eq!(get("/scramble?seed=7").1, body);
+        let scramble: MoveSequence = body["scramble"].as_str().unwrap().parse().unwrap();
+        assert_eq!(CubeSequenceRepr::from(scramble.moves()).to_facelets(), body["facelets"].as_str().unwrap());
+    }
+
+    #[test]
+    fn test_validate() {
+        let (status, body) = post("/validate", r#"{"moves": "R U"}"#);
+        assert_eq!((status, &body["valid"]), (200, &Value::Bool(true)));
+        let (status, body) = post("/validate", r#"{"facelets": "UUU"}"#);
+        assert_eq!((status, &body["valid"]), (200, &Value::Bool(false)));
+        assert!(body["error"].is_string());
+    }
+
+    #[test]
+    fn test_routes() {
+        assert_eq!(send(TestRequest::new().with_path("/solve")).0, 405);
+        assert_eq!(send(TestRequest::new().with_path("/nope")).0, 404);
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+// Minimal command line parsing: `--name value`, `-n value`, `--flag` and positionals
+
+pub struct Args {
+    pub positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+// (long name, short name, takes a value)
+pub type OptionSpec = (&'static str, Option<char>, bool);
+
+impl Args {
+    pub fn parse(args: &[String], spec: &[OptionSpec]) -> Result<Args, String> {
+        let mut res = Args {
+            positional: vec![],
+            options: HashMap::new(),
+            flags: vec![],
+        };
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let name = if let Some(long) = arg.strip_prefix("--") {
+                long.to_string()
+            } else if arg.len() == 2 && arg.starts_with('-') {
+                let short = arg.chars().nth(1).unwrap();
+                match spec.iter().find(|(_, s, _)| *s == Some(short)) {
+                    Some((long, _, _)) => long.to_string(),
+                    None => return Err(format!("unknown option '{}'", arg)),
+                }
+            } else {
+                res.positional.push(arg.clone());
+                continue;
+            };
+            // allow `--name=value` too
+            let (name, inline) = match name.find('=') {
+                Some(i) => (name[..i].to_string(), Some(name[i + 1..].to_string())),
+                None => (name, None),
+            };
+            match spec.iter().find(|(long, _, _)| *long == name) {
+                Some((_, _, true)) => {
+                    let value = match inline {
+                        Some(v) => v,
+                        None => iter.next().ok_or(format!("option '--{}' needs a value", name))?.clone(),
+                    };
+                    res.options.insert(name, value);
+                }
+                Some((_, _, false)) => res.flags.push(name),
+                None => return Err(format!("unknown option '--{}'", name)),
+            }
+        }
+        Ok(res)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(|s| s.as_str())
+    }
+
+    pub fn number<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        match self.value(name) {
+            None => Ok(None),
+            Some(v) => v.parse().map(Some).map_err(|_| format!("invalid value '{}' for '--{}'", v, name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SPEC: [OptionSpec; 3] = [("count", Some('n'), true), ("stats", None, false), ("seed", None, true)];
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        Args::parse(&args, &SPEC)
+    }
+
+    #[test]
+    fn test_parse() {
+        let args = parse(&["R", "-n", "3", "--stats", "U'", "--seed=7"]).unwrap();
+        assert_eq!(args.positional, ["R", "U'"]);
+        assert_eq!(args.number::<usize>("count"), Ok(Some(3)));
+        assert_eq!(args.number::<u64>("seed"), Ok(Some(7)));
+        assert!(args.flag("stats"));
+        assert!(!args.flag("count"));
+        assert_eq!(args.value("nope"), None);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse(&["-x"]).err().unwrap(), "unknown option '-x'");
+        assert_eq!(parse(&["--nope"]).err().unwrap(), "unknown option '--nope'");
+        assert_eq!(parse(&["--count"]).err().unwrap(), "option '--count' needs a value");
+        let args = parse(&["-n", "three"]).unwrap();
+        assert_eq!(args.number::<usize>("count"), Err("invalid value 'three' for '--count'".to_string()));
+    }
+}
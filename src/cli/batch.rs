@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::sync::mpsc::{channel, sync_channel, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde_json::json;
+
+use cube_solver::kociemba::{Solution, SolverConfig};
+use cube_solver::solver::Solver;
+
+use super::{emit, parse_cube, EXIT_FAILURE, EXIT_OK};
+
+// Batch solving: one cube per input line, one JSON object per output line, in input order
+
+pub fn run(input: Option<&str>, jobs: usize, solver: Arc<dyn Solver>, config: SolverConfig) -> Result<i32, String> {
+    let reader: Box<dyn BufRead + Send> = match input {
+        None | Some("-") => Box::new(BufReader::new(io::stdin())),
+        Some(path) => Box::new(BufReader::new(File::open(path).map_err(|e| format!("{}: {}", path, e))?)),
+    };
+    let stdout = io::stdout();
+    solve_all(reader, &mut stdout.lock(), jobs, solver, config)
+}
+
+fn solve_all<W: Write>(reader: Box<dyn BufRead + Send>, out: &mut W, jobs: usize, solver: Arc<dyn Solver>, config: SolverConfig) -> Result<i32, String> {
+    let jobs = jobs.max(1);
+    let (job_tx, job_rx) = sync_channel::<(usize, String)>(jobs);
+    let (result_tx, result_rx) = channel::<(usize, String, bool)>();
+    // one token per line the workers may be ahead of the output by, so a slow
+    // line holds up at most `jobs` finished ones behind it
+    let (token_tx, token_rx) = channel::<()>();
+    for _ in 0..jobs {
+        let _ = token_tx.send(());
+    }
+    let job_rx = Arc::new(Mutex::new(job_rx));
+    let workers: Vec<_> = (0..jobs).map(|_| {
+        let job_rx = Arc::clone(&job_rx);
+        let result_tx = result_tx.clone();
+        let solver = Arc::clone(&solver);
+        thread::spawn(move || worker(job_rx, &*solver, config, |res| result_tx.send(res).is_ok()))
+    }).collect();
+    drop(result_tx);
+
+    let feeder = thread::spawn(move || -> Result<(), String> {
+        let mut n = 0;
+        for line in reader.lines() {
+            let line = line.map_err(|e| e.to_string())?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if token_rx.recv().is_err() || job_tx.send((n, line)).is_err() {
+                break;
+            }
+            n += 1;
+        }
+        Ok(())
+    });
+
+    // workers finish out of order; hold results back until their turn comes
+    let mut pending = BTreeMap::new();
+    let mut next = 0;
+    let mut code = EXIT_OK;
+    for (n, json, ok) in result_rx {
+        if !ok {
+            code = EXIT_FAILURE;
+        }
+        pending.insert(n, json);
+        while let Some(json) = pending.remove(&next) {
+            emit(out, json)?;
+            // the reader may be done already
+            let _ = token_tx.send(());
+            next += 1;
+        }
+    }
+    for w in workers {
+        w.join().map_err(|_| "worker panicked".to_string())?;
+    }
+    feeder.join().map_err(|_| "input reader panicked".to_string())??;
+    Ok(code)
+}
+
+fn worker<F>(jobs: Arc<Mutex<Receiver<(usize, String)>>>, solver: &dyn Solver, config: SolverConfig, mut send: F)
+    where F: FnMut((usize, String, bool)) -> bool {
+    loop {
+        let job = jobs.lock().unwrap().recv();
+        let (n, line) = match job {
+            Ok(job) => job,
+            Err(_) => return,
+        };
+        let input = line.trim();
+        let result = parse_cube(input).map_err(|e| e.to_string())
+            .and_then(|cube| solver.solve(&cube, &config).map_err(|e| e.to_string()));
+        let (json, ok) = to_json(input, result);
+        if !send((n, json, ok)) {
+            return;
+        }
+    }
+}
+
+// the output line for one input line, and whether it was solved
+fn to_json(input: &str, result: Result<Solution, String>) -> (String, bool) {
+    let (value, ok) = match result {
+        Ok(s) => (json!({
+            "input": input,
+            "solution": s.moves.to_string(),
+            "length": s.moves.len(),
+            "phases": s.phases,
+            "nodes": s.nodes,
+            "time_ms": s.elapsed.as_secs_f64() * 1000.0,
+        }), true),
+        Err(e) => (json!({"input": input, "error": e}), false),
+    };
+    (value.to_string(), ok)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+    use std::time::Duration;
+    use cube_solver::kociemba::SearchStats;
+    use cube_solver::rubiks_cube::CubeSequenceRepr;
+    use cube_solver::sequence::MoveSequence;
+    use cube_solver::solver::SolveError;
+    use serde_json::Value;
+
+    // "solves" instantly, except for scrambles starting with R, which take a while
+    struct Slow;
+
+    impl Solver for Slow {
+        fn solve(&self, cube: &CubeSequenceRepr, _config: &SolverConfig) -> Result<Solution, SolveError> {
+            if *cube == CubeSequenceRepr::new() {
+                return Err(SolveError::NotFound);
+            }
+            if *cube == CubeSequenceRepr::from(&[cube_solver::rubiks_cube::Rotation::R]) {
+                thread::sleep(Duration::from_millis(200));
+            }
+            Ok(Solution {
+                moves: MoveSequence::new(),
+                phases: vec![],
+                nodes: 0,
+                elapsed: Duration::default(),
+                stats: SearchStats::default(),
+            })
+        }
+    }
+
+    fn batch(input: &str, jobs: usize) -> (i32, Vec<Value>) {
+        let mut out = vec![];
+        let code = solve_all(Box::new(Cursor::new(input.to_string())), &mut out, jobs, Arc::new(Slow), SolverConfig::default()).unwrap();
+        let lines = String::from_utf8(out).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        (code, lines)
+    }
+
+    #[test]
+    fn test_order() {
+        // the slow first line finishes last, but is still written first
+        let input = ["R", "U", "F", "D", "L", "B", "U2", "F2"];
+        let (code, lines) = batch(&input.join("\n\n"), 4);
+        assert_eq!(code, EXIT_OK);
+        let inputs: Vec<&str> = lines.iter().map(|v| v["input"].as_str().unwrap()).collect();
+        assert_eq!(inputs, input);
+        let (_, lines) = batch(&input.join("\n"), 1);
+        assert_eq!(lines.len(), input.len());
+    }
+
+    #[test]
+    fn test_escaping() {
+        let (code, lines) = batch("R \"x\\y\u{1}\nR R'", 2);
+        assert_eq!(code, EXIT_FAILURE);
+        // whatever the line held comes back out intact
+        assert_eq!(lines[0]["input"], "R \"x\\y\u{1}");
+        assert!(lines[0]["error"].as_str().unwrap().contains('"'));
+        assert_eq!(lines[1]["input"], "R R'");
+        assert_eq!(lines[1]["error"], SolveError::NotFound.to_string());
+    }
+}
@@ -0,0 +1,420 @@
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+use std::process::exit;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use cube_solver::color::{Color, ColorScheme};
+use cube_solver::kociemba::{KociembaSolver, Solution, SolverConfig};
+use cube_solver::render::ansi::{AnsiRenderer, ColorMode};
+use cube_solver::render::svg::{SvgRenderer, SvgView};
+use cube_solver::rubiks_cube::{ColoredCube, CubeSequenceRepr, Face, ParseError};
+use cube_solver::sequence::MoveSequence;
+use cube_solver::solver;
+
+use self::args::{Args, OptionSpec};
+
+mod args;
+mod batch;
+mod repl;
+
+// exit codes
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+
+// what went wrong in a command, for its exit status; plain String errors are
+// bad arguments or input
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    // the input was fine but the work couldn't be done, e.g. no solution in time
+    Failure(String),
+}
+
+impl From<String> for CliError {
+    fn from(e: String) -> Self {
+        CliError::Usage(e)
+    }
+}
+
+const USAGE: &str = "\
+Usage: cube-solver <command> [options] [args]
+
+Commands:
+  solve [MOVES]               print a solution for the scrambled cube
+  scramble                    print random-state scrambles
+  show [MOVES]                draw the cube state
+  verify SCRAMBLE SOLUTION    check that SOLUTION solves SCRAMBLE
+  batch [FILE]                solve one cube per line of FILE (or stdin) in parallel,
+                              writing one JSON object per line
+  repl                        explore cube states interactively
+
+solve and show read one cube per line from stdin when no cube is given on the
+command line. A cube is either a move sequence or a 54-character facelet
+string in U, R, F, D, L, B order.
+
+Options:
+  -f, --facelets STR    cube state as a facelet string
+  -c, --colors STR      cube state as a facelet string of colours (W, Y, R, O, G, B)
+  -n, --count N         number of scrambles to print (default 1)
+      --seed N          seed the random number generator
+  -a, --algorithm NAME  solver for solve and batch: kociemba, thistlethwaite, optimal,
+                        cross, eoline or first-block (default kociemba)
+  -l, --max-length N    stop searching once a solution this short is found (default 22)
+  -t, --timeout MS      give up after MS milliseconds (default: none, 5000 in repl)
+      --stats           make solve print search statistics to stderr
+  -j, --jobs N          worker threads for batch (default: number of CPUs)
+      --color WHEN      colour for show and repl: auto, never, 256 or truecolor (default auto)
+      --compact         draw half-height stickers, two rows per line
+      --svg VIEW        make show print an SVG image instead: net, iso or ll (last layer)
+      --png VIEW        make show write a PNG image instead, same views as --svg
+                        (needs a build with --features png)
+      --scheme NAME     colour scheme: western or japanese (default western)
+      --top C           colour on top of the cube, for --colors and show (default W)
+      --front C         colour in front of the cube, for --colors and show (default G)
+  -h, --help            print this help
+
+Exit status is 0 on success, 1 if a cube couldn't be solved or verified and
+2 on invalid usage or input.";
+
+const OPTIONS: [OptionSpec; 17] = [
+    ("facelets", Some('f'), true),
+    ("colors", Some('c'), true),
+    ("count", Some('n'), true),
+    ("seed", None, true),
+    ("algorithm", Some('a'), true),
+    ("max-length", Some('l'), true),
+    ("timeout", Some('t'), true),
+    ("stats", None, false),
+    ("jobs", Some('j'), true),
+    ("color", None, true),
+    ("compact", None, false),
+    ("svg", None, true),
+    ("png", None, true),
+    ("scheme", None, true),
+    ("top", None, true),
+    ("front", None, true),
+    ("help", Some('h'), false),
+];
+
+pub fn run(argv: &[String]) -> i32 {
+    let (command, rest) = match argv.split_first() {
+        Some((c, rest)) => (c.as_str(), rest),
+        None => {
+            eprintln!("{}", USAGE);
+            return EXIT_USAGE;
+        }
+    };
+    if command == "-h" || command == "--help" || command == "help" {
+        return print_usage();
+    }
+    let args = match Args::parse(rest, &OPTIONS) {
+        Ok(args) => args,
+        Err(e) => return usage_error(&e),
+    };
+    if args.flag("help") {
+        return print_usage();
+    }
+    let res = match command {
+        "solve" => solve(&args),
+        "scramble" => scramble(&args),
+        "show" => show(&args),
+        "verify" => verify(&args),
+        "batch" => batch(&args),
+        "repl" => repl(&args),
+        _ => return usage_error(&format!("unknown command '{}'", command)),
+    };
+    match res {
+        Ok(code) => code,
+        Err(CliError::Usage(e)) => {
+            eprintln!("error: {}", e);
+            EXIT_USAGE
+        }
+        Err(CliError::Failure(e)) => {
+            eprintln!("error: {}", e);
+            EXIT_FAILURE
+        }
+    }
+}
+
+// write a line to stdout; a closed pipe (e.g. `| head`) quietly ends the program
+pub fn emit<W: Write, T: Display>(out: &mut W, line: T) -> Result<(), String> {
+    match writeln!(out, "{}", line) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => exit(EXIT_OK),
+        r => r.map_err(|e| e.to_string()),
+    }
+}
+
+fn print_usage() -> i32 {
+    match emit(&mut io::stdout(), USAGE) {
+        Ok(()) => EXIT_OK,
+        Err(_) => EXIT_FAILURE,
+    }
+}
+
+fn usage_error(msg: &str) -> i32 {
+    eprintln!("error: {}\n\n{}", msg, USAGE);
+    EXIT_USAGE
+}
+
+// a facelet string if it looks like one, a move sequence otherwise
+pub fn parse_cube(s: &str) -> Result<CubeSequenceRepr, ParseError> {
+    let s = s.trim();
+    if s.chars().count() == 54 && s.chars().all(|c| "URFDLB".contains(c)) {
+        CubeSequenceRepr::from_facelets(s)
+    } else {
+        let moves: MoveSequence = s.parse()?;
+        Ok(CubeSequenceRepr::from(moves.moves()))
+    }
+}
+
+fn solver_config(args: &Args) -> Result<SolverConfig, String> {
+    let mut config = SolverConfig::default();
+    if let Some(n) = args.number("max-length")? {
+        config.max_length = n;
+    }
+    if let Some(ms) = args.number("timeout")? {
+        config.timeout = Some(Duration::from_millis(ms));
+    }
+    Ok(config)
+}
+
+fn algorithm(args: &Args) -> Result<Box<dyn solver::Solver>, String> {
+    let name = args.value("algorithm").unwrap_or("kociemba");
+    solver::by_name(name).ok_or_else(|| format!(
+        "unknown algorithm '{}', expected one of: {}", name, solver::ALGORITHMS.join(", ")
+    ))
+}
+
+// cubes given by --facelets or the positional arguments, or else read line by line from stdin
+fn for_each_cube<F>(args: &Args, mut f: F) -> Result<i32, String>
+    where F: FnMut(&CubeSequenceRepr) -> Result<i32, String> {
+    if let Some(facelets) = args.value("facelets") {
+        let cube = CubeSequenceRepr::from_facelets(facelets).map_err(|e| e.to_string())?;
+        return f(&cube);
+    }
+    if let Some(colors) = args.value("colors") {
+        let cube = color_scheme(args)?.from_colors(colors).map_err(|e| e.to_string())?;
+        return f(&cube);
+    }
+    if !args.positional.is_empty() {
+        let cube = parse_cube(&args.positional.join(" ")).map_err(|e| e.to_string())?;
+        return f(&cube);
+    }
+    for_each_line(io::stdin().lock(), f)
+}
+
+// one cube per line; a line that isn't a cube is reported and skipped, and
+// makes the exit status EXIT_USAGE once the rest are done
+fn for_each_line<R: BufRead, F>(input: R, mut f: F) -> Result<i32, String>
+    where F: FnMut(&CubeSequenceRepr) -> Result<i32, String> {
+    let mut code = EXIT_OK;
+    for (i, line) in input.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_cube(&line) {
+            Ok(cube) => code = code.max(f(&cube)?),
+            Err(e) => {
+                eprintln!("error: line {}: {}", i + 1, e);
+                code = EXIT_USAGE;
+            }
+        }
+    }
+    Ok(code)
+}
+
+fn solve(args: &Args) -> Result<i32, CliError> {
+    let solver = algorithm(args)?;
+    let config = solver_config(args)?;
+    let stdout = io::stdout();
+    Ok(for_each_cube(args, |cube| {
+        let mut out = stdout.lock();
+        match solver.solve(cube, &config) {
+            Ok(solution) => {
+                if args.flag("stats") {
+                    print_stats(&solution);
+                }
+                emit(&mut out, solution.moves)?;
+                Ok(EXIT_OK)
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                Ok(EXIT_FAILURE)
+            }
+        }
+    })?)
+}
+
+fn print_stats(solution: &Solution) {
+    let per_depth = |nodes: &[u64]| nodes.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(" ");
+    let stats = &solution.stats;
+    eprintln!("nodes: {} in {:.3} ms", solution.nodes, solution.elapsed.as_secs_f64() * 1000.0);
+    if !stats.phase1_nodes.is_empty() {
+        eprintln!("phase 1: {:.3} ms, {} cutoffs, nodes by depth: {}",
+                  stats.phase1_time.as_secs_f64() * 1000.0, stats.phase1_cutoffs, per_depth(&stats.phase1_nodes));
+        eprintln!("phase 2: {:.3} ms over {} runs, {} cutoffs, nodes by depth: {}",
+                  stats.phase2_time.as_secs_f64() * 1000.0, stats.phase2_runs, stats.phase2_cutoffs, per_depth(&stats.phase2_nodes));
+        for (t, len) in stats.improvements.iter() {
+            eprintln!("  {} moves after {:.3} ms", len, t.as_secs_f64() * 1000.0);
+        }
+    }
+}
+
+fn scramble(args: &Args) -> Result<i32, CliError> {
+    let count = args.number("count")?.unwrap_or(1);
+    let mut rng = match args.number("seed")? {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let solver = KociembaSolver::with_config(solver_config(args)?);
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for _ in 0..count {
+        // a random state, and the inverse of its solution to get there
+        let cube = CubeSequenceRepr::random(&mut rng);
+        let solution = solver.solve_with_own_config(&cube)
+            .ok_or_else(|| CliError::Failure("no solution found before the timeout".to_string()))?;
+        emit(&mut out, solution.moves.inverse())?;
+    }
+    Ok(EXIT_OK)
+}
+
+// the scheme with the orientation from --top and --front applied
+fn color_scheme(args: &Args) -> Result<ColorScheme, String> {
+    let scheme = match args.value("scheme") {
+        None | Some("western") => ColorScheme::western(),
+        Some("japanese") => ColorScheme::japanese(),
+        Some(v) => return Err(format!("invalid value '{}' for '--scheme'", v)),
+    };
+    let color = |name: &str, default: Color| match args.value(name) {
+        None => Ok(default.name),
+        Some(v) if v.chars().count() == 1 => Ok(v.chars().next().unwrap()),
+        Some(v) => Err(format!("invalid value '{}' for '--{}'", v, name)),
+    };
+    let (top, front) = (color("top", scheme.color(Face::U))?, color("front", scheme.color(Face::F))?);
+    scheme.oriented(top, front).map_err(|e| e.to_string())
+}
+
+fn renderer(args: &Args) -> Result<AnsiRenderer, String> {
+    let mut renderer = match args.value("color") {
+        None | Some("auto") => AnsiRenderer::detect(),
+        Some("never") => AnsiRenderer::new(ColorMode::Plain, false),
+        Some("256") => AnsiRenderer::new(ColorMode::Ansi256, false),
+        Some("truecolor") => AnsiRenderer::new(ColorMode::TrueColor, false),
+        Some(v) => return Err(format!("invalid value '{}' for '--color'", v)),
+    };
+    renderer.compact = args.flag("compact");
+    Ok(renderer)
+}
+
+fn image_view(args: &Args, option: &str) -> Result<Option<SvgView>, String> {
+    match args.value(option) {
+        None => Ok(None),
+        Some("net") => Ok(Some(SvgView::Net)),
+        Some("iso") => Ok(Some(SvgView::Isometric)),
+        Some("ll") => Ok(Some(SvgView::LastLayer)),
+        Some(v) => Err(format!("invalid value '{}' for '--{}'", v, option)),
+    }
+}
+
+fn show(args: &Args) -> Result<i32, CliError> {
+    let renderer = renderer(args)?;
+    let svg = image_view(args, "svg")?;
+    let png = image_view(args, "png")?;
+    if cfg!(not(feature = "png")) && png.is_some() {
+        return Err(CliError::Usage("this build has no PNG support, rebuild with --features png".to_string()));
+    }
+    let scheme = color_scheme(args)?;
+    let stdout = io::stdout();
+    Ok(for_each_cube(args, |cube| {
+        let mut out = stdout.lock();
+        let mut vis = ColoredCube::with_scheme(scheme);
+        cube.visualize(&mut vis);
+        if let Some(view) = svg {
+            return emit(&mut out, SvgRenderer::new().render(&vis, view).trim_end()).map(|_| EXIT_OK);
+        }
+        #[cfg(feature = "png")]
+        if let Some(view) = png {
+            let image = cube_solver::render::png::PngRenderer::new().render(&vis, view).map_err(|e| e.to_string())?;
+            return match out.write_all(&image).and_then(|_| out.flush()) {
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => exit(EXIT_OK),
+                r => r.map(|_| EXIT_OK).map_err(|e| e.to_string()),
+            };
+        }
+        emit(&mut out, renderer.render(&vis))?;
+        emit(&mut out, cube.to_facelets())?;
+        if scheme != ColorScheme::western() {
+            emit(&mut out, scheme.to_colors(cube))?;
+        }
+        Ok(EXIT_OK)
+    })?)
+}
+
+fn verify(args: &Args) -> Result<i32, CliError> {
+    if args.positional.len() != 2 {
+        return Err(CliError::Usage("verify takes a scramble and a solution".to_string()));
+    }
+    let cube = parse_cube(&args.positional[0]).map_err(|e| e.to_string())?;
+    let solution: MoveSequence = args.positional[1].parse().map_err(|e: ParseError| e.to_string())?;
+    if cube.multiply(&CubeSequenceRepr::from(solution.moves())) == CubeSequenceRepr::new() {
+        emit(&mut io::stdout(), "ok")?;
+        Ok(EXIT_OK)
+    } else {
+        emit(&mut io::stdout(), "not solved")?;
+        Ok(EXIT_FAILURE)
+    }
+}
+
+fn batch(args: &Args) -> Result<i32, CliError> {
+    if args.positional.len() > 1 {
+        return Err(CliError::Usage("batch takes at most one input file".to_string()));
+    }
+    let jobs = match args.number("jobs")? {
+        Some(n) => n,
+        None => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+    };
+    let solver = algorithm(args)?.into();
+    Ok(batch::run(args.positional.first().map(|s| s.as_str()), jobs, solver, solver_config(args)?)?)
+}
+
+fn repl(args: &Args) -> Result<i32, CliError> {
+    Ok(repl::run(solver_config(args)?, renderer(args)?, color_scheme(args)?)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_cube() {
+        let facelets = CubeSequenceRepr::from(&[cube_solver::rubiks_cube::Rotation::R]).to_facelets();
+        assert_eq!(parse_cube(&facelets).unwrap(), parse_cube("R").unwrap());
+        assert_eq!(parse_cube(" R U  ").unwrap(), parse_cube("R U").unwrap());
+        // facelet-shaped but no cube: too many of one colour, a swapped centre
+        let wrong = format!("U{}", &facelets[1..].replacen('R', "U", 1));
+        assert!(matches!(parse_cube(&wrong), Err(ParseError::InvalidFacelets(_))));
+        let centres = format!("{}R{}", &facelets[..4], &facelets[5..]);
+        assert!(matches!(parse_cube(&centres), Err(ParseError::InvalidFacelets(_))));
+        assert!(parse_cube("R X").is_err());
+    }
+
+    #[test]
+    fn test_for_each_line() {
+        let input = "R U\n\nnot a cube\nF'\n";
+        let mut seen = vec![];
+        let code = for_each_line(input.as_bytes(), |cube| {
+            seen.push(*cube);
+            Ok(EXIT_OK)
+        });
+        // the bad line doesn't stop the ones after it
+        assert_eq!(code, Ok(EXIT_USAGE));
+        assert_eq!(seen, [parse_cube("R U").unwrap(), parse_cube("F'").unwrap()]);
+        let code = for_each_line("R\nU\n".as_bytes(), |_| Ok(EXIT_FAILURE));
+        assert_eq!(code, Ok(EXIT_FAILURE));
+    }
+}
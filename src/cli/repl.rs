@@ -0,0 +1,176 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::time::Duration;
+
+use cube_solver::color::ColorScheme;
+use cube_solver::kociemba::{KociembaSolver, SolverConfig};
+use cube_solver::render::ansi::AnsiRenderer;
+use cube_solver::rubiks_cube::{ColoredCube, CubeSequenceRepr, ParseError};
+use cube_solver::sequence::MoveSequence;
+
+use super::{emit, EXIT_OK};
+
+// Interactive exploration of a cube state
+
+const HELP: &str = "\
+Type moves (e.g. R U R' U') to apply them, or one of:
+  undo              take back the last change
+  reset             go back to the solved cube
+  set FACELETS      replace the cube with a facelet string
+  invert            replace the cube with its inverse
+  solve             print a solution for the current cube
+  order             print the order and cycle structure of the current cube
+  history           list the changes made so far
+  help              print this help
+  quit              leave";
+
+// how long `solve` may take without --timeout, so that the prompt always comes
+// back; the best solution found by then is printed
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Session {
+    cube: CubeSequenceRepr,
+    // the state before each change, for undo, along with what the change was
+    history: Vec<(CubeSequenceRepr, String)>,
+    solver: KociembaSolver,
+}
+
+impl Session {
+    fn new(config: SolverConfig) -> Self {
+        let config = SolverConfig { timeout: config.timeout.or(Some(DEFAULT_TIMEOUT)), ..config };
+        Session {
+            cube: CubeSequenceRepr::new(),
+            history: vec![],
+            solver: KociembaSolver::with_config(config),
+        }
+    }
+
+    fn change(&mut self, cube: CubeSequenceRepr, what: String) {
+        self.history.push((self.cube, what));
+        self.cube = cube;
+    }
+
+    // returns what to print, and whether to redraw the cube
+    fn exec(&mut self, line: &str) -> Result<(String, bool), String> {
+        let (cmd, rest) = match line.split_once(char::is_whitespace) {
+            Some((cmd, rest)) => (cmd, rest.trim()),
+            None => (line, ""),
+        };
+        match cmd {
+            "help" | "?" => Ok((HELP.to_string(), false)),
+            "undo" => match self.history.pop() {
+                Some((cube, what)) => {
+                    self.cube = cube;
+                    Ok((format!("undid {}", what), true))
+                }
+                None => Err("nothing to undo".to_string()),
+            },
+            "reset" => {
+                self.change(CubeSequenceRepr::new(), "reset".to_string());
+                Ok((String::new(), true))
+            }
+            "set" => {
+                let cube = CubeSequenceRepr::from_facelets(rest).map_err(|e| e.to_string())?;
+                self.change(cube, format!("set {}", rest));
+                Ok((String::new(), true))
+            }
+            "invert" => {
+                self.change(self.cube.inverse(), "invert".to_string());
+                Ok((String::new(), true))
+            }
+            "solve" => match self.solver.solve_with_own_config(&self.cube) {
+                Some(s) => Ok((format!("{} ({} moves)", s.moves, s.moves.len()), false)),
+                None => Err("no solution found".to_string()),
+            },
+            "order" => Ok((format!("order {}: {}", self.cube.order(), self.cube.cycles()), false)),
+            "history" => {
+                let lines: Vec<String> = self.history.iter().enumerate()
+                    .map(|(i, (_, what))| format!("{:4}  {}", i + 1, what))
+                    .collect();
+                Ok((lines.join("\n"), false))
+            }
+            _ => {
+                let moves: MoveSequence = line.parse().map_err(|e: ParseError| format!("{} (try 'help')", e))?;
+                if moves.is_empty() {
+                    return Ok((String::new(), false));
+                }
+                let cube = self.cube.multiply(&CubeSequenceRepr::from(moves.moves()));
+                self.change(cube, moves.to_string());
+                Ok((String::new(), true))
+            }
+        }
+    }
+}
+
+pub fn run(config: SolverConfig, renderer: AnsiRenderer, scheme: ColorScheme) -> Result<i32, String> {
+    let mut session = Session::new(config);
+    let interactive = io::stdin().is_terminal();
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        if interactive {
+            write!(out, "> ").and_then(|_| out.flush()).map_err(|e| e.to_string())?;
+        }
+        let line = match lines.next() {
+            Some(line) => line.map_err(|e| e.to_string())?,
+            None => break,
+        };
+        let line = line.trim();
+        if line == "quit" || line == "exit" {
+            break;
+        }
+        match session.exec(line) {
+            Ok((msg, redraw)) => {
+                if !msg.is_empty() {
+                    emit(&mut out, msg)?;
+                }
+                if redraw {
+                    let mut vis = ColoredCube::with_scheme(scheme);
+                    session.cube.visualize(&mut vis);
+                    emit(&mut out, renderer.render(&vis))?;
+                }
+            }
+            Err(e) => eprintln!("error: {}", e),
+        }
+    }
+    Ok(EXIT_OK)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_exec() {
+        let mut session = Session::new(SolverConfig::default());
+        assert_eq!(session.exec("R U"), Ok((String::new(), true)));
+        assert_eq!(session.exec("  "), Ok((String::new(), false)));
+        assert_eq!(session.exec("invert"), Ok((String::new(), true)));
+        assert_eq!(session.cube, CubeSequenceRepr::from("U' R'".parse::<MoveSequence>().unwrap().moves()));
+        assert_eq!(session.exec("history").unwrap().0, "   1  R U\n   2  invert");
+        assert_eq!(session.exec("undo"), Ok(("undid invert".to_string(), true)));
+        assert_eq!(session.exec("order").unwrap().0, format!("order 105: {}", session.cube.cycles()));
+        session.exec("reset").unwrap();
+        assert_eq!(session.cube, CubeSequenceRepr::new());
+        // back through the reset and the moves, then nothing left
+        session.exec("undo").unwrap();
+        session.exec("undo").unwrap();
+        assert!(session.exec("undo").is_err());
+        assert!(session.exec("X").unwrap_err().contains("try 'help'"));
+        assert!(session.exec("set UUU").is_err());
+        assert!(session.exec("help").unwrap().0.starts_with("Type moves"));
+    }
+
+    #[test]
+    fn test_solve() {
+        let mut session = Session::new(SolverConfig::default());
+        assert_eq!(session.solver.config().timeout, Some(DEFAULT_TIMEOUT));
+        session.exec("R U F").unwrap();
+        assert_eq!(session.exec("solve").unwrap().0, "F' U' R' (3 moves)");
+        // nothing this short exists, so only the timeout gets it back
+        let mut session = Session::new(SolverConfig { max_length: 1, timeout: Some(Duration::from_millis(100)) });
+        session.exec("R U F D L B").unwrap();
+        let (msg, redraw) = session.exec("solve").unwrap();
+        assert!(msg.ends_with("moves)") && !redraw);
+    }
+}
@@ -0,0 +1,162 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::num_traits::{FromPrimitive, ToPrimitive};
+use crate::rubiks_cube::{CubeSequenceRepr, Face, ParseError};
+
+// Sticker colours, and which colour ends up on which face of a cube
+//
+// The solver only knows faces; a ColorScheme says what colour each face's
+// centre has when the cube is held the standard way up, and how to read and
+// write facelet strings written in colours for a cube held some other way.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Color {
+    // the letter used for this colour in text output and colour facelet strings
+    pub name: char,
+    pub rgb: (u8, u8, u8),
+}
+
+impl Color {
+    pub const fn new(name: char, rgb: (u8, u8, u8)) -> Self {
+        Color { name, rgb }
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+pub const WHITE: Color = Color::new('W', (255, 255, 255));
+pub const YELLOW: Color = Color::new('Y', (255, 213, 0));
+pub const RED: Color = Color::new('R', (196, 30, 58));
+pub const ORANGE: Color = Color::new('O', (255, 88, 0));
+pub const GREEN: Color = Color::new('G', (0, 158, 96));
+pub const BLUE: Color = Color::new('B', (0, 81, 186));
+
+// outward normal of each face as (x, y, z): x towards R, y towards U, z towards F
+const NORMALS: [[i8; 3]; 6] = [
+    [0, 1, 0], [0, -1, 0], [1, 0, 0], [-1, 0, 0], [0, 0, 1], [0, 0, -1]
+];
+
+fn face_with_normal(n: [i8; 3]) -> Face {
+    Face::from_usize(NORMALS.iter().position(|&m| m == n).unwrap()).unwrap()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ColorScheme {
+    // indexed by Face
+    colors: [Color; 6],
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme::western()
+    }
+}
+
+impl ColorScheme {
+    // colours for U, D, R, L, F, B; they have to have different names
+    pub fn new(colors: [Color; 6]) -> std::result::Result<Self, ParseError> {
+        for i in 0..6 {
+            if colors[i + 1..].iter().any(|c| c.name == colors[i].name) {
+                return Err(ParseError::InvalidColors(format!("'{}' is used for two faces", colors[i].name)));
+            }
+        }
+        Ok(ColorScheme { colors })
+    }
+
+    // white on top, green in front
+    pub fn western() -> Self {
+        ColorScheme { colors: [WHITE, YELLOW, RED, ORANGE, GREEN, BLUE] }
+    }
+
+    // blue opposite white and yellow opposite green
+    pub fn japanese() -> Self {
+        ColorScheme { colors: [WHITE, BLUE, RED, ORANGE, GREEN, YELLOW] }
+    }
+
+    pub fn color(&self, face: Face) -> Color {
+        self.colors[face.to_usize().unwrap()]
+    }
+
+    pub fn face_of(&self, name: char) -> Option<Face> {
+        self.colors.iter().position(|c| c.name == name).and_then(Face::from_usize)
+    }
+
+    // the same cube held with `top` on U and `front` on F
+    pub fn oriented(&self, top: char, front: char) -> std::result::Result<Self, ParseError> {
+        let unknown = |c| ParseError::InvalidColors(format!("'{}' isn't in the colour scheme", c));
+        let t = NORMALS[self.face_of(top).ok_or_else(|| unknown(top))?.to_usize().unwrap()];
+        let f = NORMALS[self.face_of(front).ok_or_else(|| unknown(front))?.to_usize().unwrap()];
+        let r = [t[1] * f[2] - t[2] * f[1], t[2] * f[0] - t[0] * f[2], t[0] * f[1] - t[1] * f[0]];
+        if r == [0, 0, 0] {
+            return Err(ParseError::InvalidColors(format!("'{}' and '{}' can't be on top and in front at once", top, front)));
+        }
+        // the face that ends up where `n` points is a * top + b * front + c * right,
+        // with (c, a, b) the coordinates of n
+        let mut colors = self.colors;
+        for (i, n) in NORMALS.iter().enumerate() {
+            let mut v = [0; 3];
+            for (k, x) in v.iter_mut().enumerate() {
+                *x = n[1] * t[k] + n[2] * f[k] + n[0] * r[k];
+            }
+            colors[i] = self.color(face_with_normal(v));
+        }
+        Ok(ColorScheme { colors })
+    }
+
+    // like CubeSequenceRepr::from_facelets, but with colour names instead of face names
+    pub fn from_colors(&self, s: &str) -> std::result::Result<CubeSequenceRepr, ParseError> {
+        let facelets = s.trim().chars()
+            .map(|c| self.face_of(c).map(Face::to_char)
+                .ok_or_else(|| ParseError::InvalidColors(format!("'{}' isn't in the colour scheme", c))))
+            .collect::<std::result::Result<String, ParseError>>()?;
+        CubeSequenceRepr::from_facelets(&facelets)
+    }
+
+    pub fn to_colors(&self, cube: &CubeSequenceRepr) -> String {
+        cube.to_facelets().chars()
+            .map(|c| self.color(Face::from_char(c).unwrap()).name)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rubiks_cube::Rotation;
+
+    #[test]
+    fn test_oriented() {
+        let s = ColorScheme::western();
+        assert_eq!(s.oriented('W', 'G').unwrap(), s);
+        // yellow on top and green in front: turned over around the R-L axis
+        let o = s.oriented('Y', 'G').unwrap();
+        assert_eq!(o.color(Face::F), GREEN);
+        assert_eq!(o.color(Face::B), BLUE);
+        assert_eq!(o.color(Face::R), ORANGE);
+        assert_eq!(o.color(Face::L), RED);
+        assert_eq!(o.color(Face::D), WHITE);
+        // white on top, red in front: turned a quarter around U
+        let o = s.oriented('W', 'R').unwrap();
+        assert_eq!(o.color(Face::R), BLUE);
+        assert_eq!(o.color(Face::L), GREEN);
+        assert!(s.oriented('W', 'Y').is_err());
+        assert!(s.oriented('W', 'X').is_err());
+    }
+
+    #[test]
+    fn test_colors() {
+        let cube = CubeSequenceRepr::from(&[Rotation::R, Rotation::U, Rotation::Fr]);
+        for s in [ColorScheme::western(), ColorScheme::japanese()].iter() {
+            for &(t, f) in [('W', 'G'), ('Y', 'R'), ('B', 'O')].iter() {
+                let s = s.oriented(t, f).unwrap();
+                assert_eq!(s.from_colors(&s.to_colors(&cube)).unwrap(), cube);
+            }
+        }
+        assert_eq!(&ColorScheme::western().to_colors(&CubeSequenceRepr::new())[..18], "WWWWWWWWWRRRRRRRRR");
+        assert!(ColorScheme::new([WHITE, WHITE, RED, ORANGE, GREEN, BLUE]).is_err());
+    }
+}
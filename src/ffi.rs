@@ -0,0 +1,126 @@
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::panic::catch_unwind;
+use std::time::Duration;
+
+use crate::heuristic::init_tables;
+use crate::kociemba::{KociembaSolver, SolverConfig};
+use crate::rubiks_cube::{CubeSequenceRepr, ParseError};
+use crate::sequence::MoveSequence;
+
+// C ABI for embedding the solver, declared in include/cube_solver.h
+//
+// Everything returns one of the codes below (or a non-negative count on
+// success); nothing here panics across the boundary.
+
+pub const CS_OK: c_int = 0;
+pub const CS_ERR_NULL_POINTER: c_int = -1;
+pub const CS_ERR_INVALID_FACELETS: c_int = -2;
+pub const CS_ERR_UNSOLVABLE: c_int = -3;
+pub const CS_ERR_NO_SOLUTION: c_int = -4;
+pub const CS_ERR_BUFFER_TOO_SMALL: c_int = -5;
+pub const CS_ERR_INTERNAL: c_int = -6;
+
+// builds the pruning tables, which otherwise happens on the first solve; they
+// are shared process-wide and stay around until exit
+#[no_mangle]
+pub extern "C" fn cs_init_tables() -> c_int {
+    match catch_unwind(init_tables) {
+        Ok(()) => CS_OK,
+        Err(_) => CS_ERR_INTERNAL,
+    }
+}
+
+// solves the cube given as a NUL-terminated 54-character facelet string and
+// writes the solution, NUL-terminated, to `out`. `max_len` and `timeout_ms`
+// fall back to the defaults (22 moves, no timeout) when not positive.
+// Returns the number of moves in the solution, or a negative error code.
+//
+// Safety: a non-null `facelets` must point to a NUL-terminated string that is
+// valid for reads up to and including the NUL, and a non-null `out` must be
+// valid for writes of `len` bytes; neither may change during the call. NULL
+// for either is caught and returns CS_ERR_NULL_POINTER.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn cs_solve_facelets(facelets: *const c_char, out: *mut c_char, len: usize,
+                                           max_len: c_int, timeout_ms: c_int) -> c_int {
+    if facelets.is_null() || out.is_null() {
+        return CS_ERR_NULL_POINTER;
+    }
+    let facelets = match CStr::from_ptr(facelets).to_str() {
+        Ok(s) => s,
+        Err(_) => return CS_ERR_INVALID_FACELETS,
+    };
+    let mut config = SolverConfig::default();
+    if max_len > 0 {
+        config.max_length = max_len as usize;
+    }
+    if timeout_ms > 0 {
+        config.timeout = Some(Duration::from_millis(timeout_ms as u64));
+    }
+    let solution = match catch_unwind(|| solve(facelets, config)) {
+        Ok(Ok(s)) => s,
+        Ok(Err(code)) => return code,
+        Err(_) => return CS_ERR_INTERNAL,
+    };
+    let text = solution.to_string();
+    if text.len() + 1 > len {
+        return CS_ERR_BUFFER_TOO_SMALL;
+    }
+    std::ptr::copy_nonoverlapping(text.as_ptr(), out as *mut u8, text.len());
+    *out.add(text.len()) = 0;
+    solution.len() as c_int
+}
+
+fn solve(facelets: &str, config: SolverConfig) -> Result<MoveSequence, c_int> {
+    let cube = CubeSequenceRepr::from_facelets(facelets).map_err(|e| match e {
+        ParseError::Unsolvable(_) => CS_ERR_UNSOLVABLE,
+        _ => CS_ERR_INVALID_FACELETS,
+    })?;
+    KociembaSolver::with_config(config).solve_with_own_config(&cube).map(|s| s.moves).ok_or(CS_ERR_NO_SOLUTION)
+}
+
+// a static description of an error code, never NULL
+#[no_mangle]
+pub extern "C" fn cs_error_message(code: c_int) -> *const c_char {
+    let msg: &'static [u8] = match code {
+        CS_OK => b"ok\0",
+        CS_ERR_NULL_POINTER => b"null pointer argument\0",
+        CS_ERR_INVALID_FACELETS => b"invalid facelet string\0",
+        CS_ERR_UNSOLVABLE => b"cube is not solvable\0",
+        CS_ERR_NO_SOLUTION => b"no solution found within the length and time limits\0",
+        CS_ERR_BUFFER_TOO_SMALL => b"output buffer too small\0",
+        CS_ERR_INTERNAL => b"internal error\0",
+        _ => b"unknown error code\0",
+    };
+    msg.as_ptr() as *const c_char
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::CString;
+    use crate::rubiks_cube::Rotation;
+
+    #[test]
+    fn test_solve_facelets() {
+        let cube = CubeSequenceRepr::from(&[Rotation::R, Rotation::U]);
+        let facelets = CString::new(cube.to_facelets()).unwrap();
+        let mut out = [0 as c_char; 64];
+        let n = unsafe { cs_solve_facelets(facelets.as_ptr(), out.as_mut_ptr(), out.len(), 0, 0) };
+        assert_eq!(n, 2);
+        assert_eq!(unsafe { CStr::from_ptr(out.as_ptr()) }.to_str().unwrap(), "U' R'");
+        let n = unsafe { cs_solve_facelets(facelets.as_ptr(), out.as_mut_ptr(), 5, 0, 0) };
+        assert_eq!(n, CS_ERR_BUFFER_TOO_SMALL);
+
+        // UF flipped in place
+        let mut flipped: Vec<u8> = CubeSequenceRepr::new().to_facelets().into_bytes();
+        flipped.swap(7, 19);
+        let flipped = CString::new(flipped).unwrap();
+        assert_eq!(unsafe { cs_solve_facelets(flipped.as_ptr(), out.as_mut_ptr(), out.len(), 0, 0) }, CS_ERR_UNSOLVABLE);
+
+        let bad = CString::new("UUU").unwrap();
+        assert_eq!(unsafe { cs_solve_facelets(bad.as_ptr(), out.as_mut_ptr(), out.len(), 0, 0) }, CS_ERR_INVALID_FACELETS);
+        assert_eq!(unsafe { cs_solve_facelets(std::ptr::null(), out.as_mut_ptr(), out.len(), 0, 0) }, CS_ERR_NULL_POINTER);
+    }
+}
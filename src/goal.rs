@@ -0,0 +1,352 @@
+use std::fmt::{Display, Formatter};
+
+use crate::heuristic::{prune_move, PruneTable, ALL_MOVES, PHASE1_EDGEORIENT_PT};
+use crate::num_traits::ToPrimitive;
+use crate::rubiks_cube::{eo_encode, CubeRepr, EdgeOrient, CubeSequenceRepr, Face, Rotation, CORNER_GROUP, CORNER_MAP, EDGE_GROUP, EDGE_MAP};
+use crate::sequence::MoveSequence;
+use crate::solver::Budget;
+
+// Partial-goal solving: cross, F2L pairs, EOLine, first block
+
+// pieces tracked by a single pruning table; each one takes 24 slots (12 positions x 2 flips
+// for edges, 8 positions x 3 twists for corners), so 4 pieces make a 331776 entry table
+pub const MAX_GROUP_PIECES: usize = 4;
+const PIECE_STATES: usize = 24;
+
+lazy_static! {
+    // where a piece at each position ends up after a move, and how its orientation changes
+    static ref EDGE_TRANSITIONS: [[(u8, u8); 12]; 18] = {
+        let mut res = [[(0, 0); 12]; 18];
+        for r in ALL_MOVES.iter() {
+            let m = CubeSequenceRepr::from(&[*r]);
+            for i in 0..12 {
+                res[r.to_usize().unwrap()][m.ep.0[i] as usize] = (i as u8, m.eo.0[i] as u8);
+            }
+        }
+        res
+    };
+    static ref CORNER_TRANSITIONS: [[(u8, u8); 8]; 18] = {
+        let mut res = [[(0, 0); 8]; 18];
+        for r in ALL_MOVES.iter() {
+            let m = CubeSequenceRepr::from(&[*r]);
+            for i in 0..8 {
+                res[r.to_usize().unwrap()][m.cp.0[i] as usize] = (i as u8, m.co.0[i]);
+            }
+        }
+        res
+    };
+}
+
+// Positions and orientations of a handful of tracked pieces
+#[derive(Copy, Clone)]
+pub struct PieceSet {
+    // (position, flip) of each tracked edge
+    edges: [(u8, u8); MAX_GROUP_PIECES],
+    // (position, twist) of each tracked corner
+    corners: [(u8, u8); MAX_GROUP_PIECES],
+    n_edges: usize,
+    n_corners: usize,
+}
+
+impl PieceSet {
+    // the tracked pieces sitting solved at home
+    pub(crate) fn solved(group: &PieceGroup) -> Self {
+        let mut res = PieceSet {
+            edges: [(0, 0); MAX_GROUP_PIECES],
+            corners: [(0, 0); MAX_GROUP_PIECES],
+            n_edges: group.edges.len(),
+            n_corners: group.corners.len(),
+        };
+        for (i, &e) in group.edges.iter().enumerate() {
+            res.edges[i] = (e, 0);
+        }
+        for (i, &c) in group.corners.iter().enumerate() {
+            res.corners[i] = (c, 0);
+        }
+        res
+    }
+
+    // None if a piece of the group isn't on the cube at all
+    pub(crate) fn from_cube(group: &PieceGroup, cube: &CubeSequenceRepr) -> Option<Self> {
+        let mut res = PieceSet::solved(group);
+        for (i, &e) in group.edges.iter().enumerate() {
+            let pos = cube.ep.0.iter().position(|&x| x == e)?;
+            res.edges[i] = (pos as u8, cube.eo.0[pos] as u8);
+        }
+        for (i, &c) in group.corners.iter().enumerate() {
+            let pos = cube.cp.0.iter().position(|&x| x == c)?;
+            res.corners[i] = (pos as u8, cube.co.0[pos]);
+        }
+        Some(res)
+    }
+
+    pub(crate) fn table_size(group: &PieceGroup) -> usize {
+        PIECE_STATES.pow((group.edges.len() + group.corners.len()) as u32)
+    }
+
+    pub fn encode(&self) -> usize {
+        let mut res = 0;
+        for &(pos, flip) in self.edges[..self.n_edges].iter() {
+            res = res * PIECE_STATES + (pos as usize * 2 + flip as usize);
+        }
+        for &(pos, twist) in self.corners[..self.n_corners].iter() {
+            res = res * PIECE_STATES + (pos as usize * 3 + twist as usize);
+        }
+        res
+    }
+}
+
+impl CubeRepr for PieceSet {
+    fn rotate(&mut self, r: Rotation) {
+        let idx = r.to_usize().unwrap();
+        for e in self.edges[..self.n_edges].iter_mut() {
+            let (pos, flip) = EDGE_TRANSITIONS[idx][e.0 as usize];
+            *e = (pos, e.1 ^ flip);
+        }
+        for c in self.corners[..self.n_corners].iter_mut() {
+            let (pos, twist) = CORNER_TRANSITIONS[idx][c.0 as usize];
+            *c = (pos, (c.1 + twist) % 3);
+        }
+    }
+}
+
+// Pieces (by their solved position) that have to be solved and oriented together
+#[derive(Debug, Clone)]
+pub struct PieceGroup {
+    pub edges: Vec<u8>,
+    pub corners: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoalError {
+    // more than MAX_GROUP_PIECES pieces in one group, and how many
+    TooManyPieces(usize),
+    NoSuchEdge(u8),
+    NoSuchCorner(u8),
+    // xcross slots are the 4 corners of the cross face
+    NoSuchSlot(usize),
+}
+
+impl Display for GoalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoalError::TooManyPieces(n) => write!(f, "{} pieces in one group, at most {} fit a table", n, MAX_GROUP_PIECES),
+            GoalError::NoSuchEdge(e) => write!(f, "no edge {}, edges are 0 to 11", e),
+            GoalError::NoSuchCorner(c) => write!(f, "no corner {}, corners are 0 to 7", c),
+            GoalError::NoSuchSlot(s) => write!(f, "no slot {}, slots are 0 to 3", s),
+        }
+    }
+}
+
+impl std::error::Error for GoalError {}
+
+// A partial goal: every piece group solved, and optionally all 12 edges oriented
+#[derive(Debug, Clone, Default)]
+pub struct Goal {
+    pub groups: Vec<PieceGroup>,
+    pub orient_edges: bool,
+}
+
+// the F2L edge sitting between the two faces of `corner` other than `face`
+fn pair_edge(face: Face, corner: usize) -> u8 {
+    let sides: Vec<Face> = CORNER_MAP[corner].iter().cloned().filter(|f| *f != face).collect();
+    EDGE_MAP.iter().position(|e| sides.contains(&e[0]) && sides.contains(&e[1])).unwrap() as u8
+}
+
+impl Goal {
+    pub fn new() -> Self {
+        Goal {
+            groups: vec![],
+            orient_edges: false,
+        }
+    }
+
+    // require the given edges and corners solved; each call gets its own pruning table,
+    // so keep it to MAX_GROUP_PIECES pieces and split bigger goals into several groups
+    pub fn with_pieces(self, edges: &[u8], corners: &[u8]) -> Result<Self, GoalError> {
+        if edges.len() + corners.len() > MAX_GROUP_PIECES {
+            return Err(GoalError::TooManyPieces(edges.len() + corners.len()));
+        }
+        if let Some(&e) = edges.iter().find(|&&e| e >= 12) {
+            return Err(GoalError::NoSuchEdge(e));
+        }
+        if let Some(&c) = corners.iter().find(|&&c| c >= 8) {
+            return Err(GoalError::NoSuchCorner(c));
+        }
+        Ok(self.group(edges, corners))
+    }
+
+    // with_pieces for the goals below, whose pieces are known to fit
+    fn group(mut self, edges: &[u8], corners: &[u8]) -> Self {
+        self.groups.push(PieceGroup {
+            edges: edges.to_vec(),
+            corners: corners.to_vec(),
+        });
+        self
+    }
+
+    // require all edges oriented, in the sense of EdgeOrient (no R/L quarter turns needed)
+    pub fn with_oriented_edges(mut self) -> Self {
+        self.orient_edges = true;
+        self
+    }
+
+    // the four edges of `face`
+    pub fn cross(face: Face) -> Self {
+        let edges: Vec<u8> = EDGE_GROUP[face.to_usize().unwrap()].iter().map(|&e| e as u8).collect();
+        Goal::new().group(&edges, &[])
+    }
+
+    // cross plus the F2L pair at the `slot`th corner (0..4) of `face`
+    pub fn xcross(face: Face, slot: usize) -> Result<Self, GoalError> {
+        let corner = *CORNER_GROUP[face.to_usize().unwrap()].get(slot).ok_or(GoalError::NoSuchSlot(slot))?;
+        Ok(Goal::cross(face).group(&[pair_edge(face, corner)], &[corner as u8]))
+    }
+
+    // all edges oriented plus the DL and DR edges; since EdgeOrient is relative to
+    // R/L quarter turns the line runs left to right, leaving <U, F, B> for F2L
+    pub fn eoline() -> Self {
+        Goal::new().with_oriented_edges().group(&[9, 11], &[])
+    }
+
+    // Roux first block: the 1x2x3 on the left, DL/LF/LB edges and DLF/DBL corners
+    pub fn first_block() -> Self {
+        Goal::new().group(&[11, 7, 4], &[]).group(&[11], &[7, 4])
+    }
+
+    pub fn is_solved(&self, cube: &CubeSequenceRepr) -> bool {
+        let pieces = self.groups.iter().all(|g| {
+            g.edges.iter().all(|&e| cube.ep.0[e as usize] == e && !cube.eo.0[e as usize]) &&
+                g.corners.iter().all(|&c| cube.cp.0[c as usize] == c && cube.co.0[c as usize] == 0)
+        });
+        pieces && (!self.orient_edges || !cube.eo.0.contains(&true))
+    }
+}
+
+// IDA* solver for a partial goal, with one pruning table per piece group
+pub struct GoalSolver {
+    goal: Goal,
+    tables: Vec<PruneTable<PieceSet>>,
+}
+
+impl GoalSolver {
+    pub fn new(goal: Goal) -> Self {
+        let tables = goal.groups.iter().map(|g| {
+            let mut pt = PruneTable::new(PieceSet::table_size(g));
+            pt.init(PieceSet::encode, &[PieceSet::solved(g)], &ALL_MOVES);
+            pt
+        }).collect();
+        GoalSolver { goal, tables }
+    }
+
+    pub fn goal(&self) -> &Goal {
+        &self.goal
+    }
+
+    // shortest sequence reaching the goal, or None if there is none within `max_depth` moves
+    pub fn solve(&self, cube: &CubeSequenceRepr, max_depth: usize) -> Option<MoveSequence> {
+        self.solve_within(cube, max_depth, &mut Budget::new(None))
+    }
+
+    // the same, giving up once `budget` runs out
+    pub(crate) fn solve_within(&self, cube: &CubeSequenceRepr, max_depth: usize, budget: &mut Budget) -> Option<MoveSequence> {
+        let mut state: Vec<PieceSet> = self.goal.groups.iter().map(|g| PieceSet::from_cube(g, cube)).collect::<Option<_>>()?;
+        let eo = cube.eo;
+        let mut moves = vec![];
+        // depths are i8 in the tables, and nothing here is anywhere near 127 moves
+        let max_depth = max_depth.min(i8::MAX as usize) as i8;
+        for depth in self.h(&state, eo)..=max_depth {
+            if self.search(&mut state, eo, depth, &mut moves, budget) {
+                return Some(MoveSequence::from(moves));
+            }
+            if budget.expired {
+                break;
+            }
+        }
+        None
+    }
+
+    fn h(&self, state: &[PieceSet], eo: EdgeOrient) -> i8 {
+        let mut res = if self.goal.orient_edges { PHASE1_EDGEORIENT_PT[eo_encode(&eo)] } else { 0 };
+        for (pt, s) in self.tables.iter().zip(state.iter()) {
+            res = res.max(pt[s.encode()]);
+        }
+        res
+    }
+
+    // turns `state` in place, and back again for every move that doesn't lead to
+    // a solution
+    fn search(&self, state: &mut [PieceSet], eo: EdgeOrient, depth: i8, moves: &mut Vec<Rotation>, budget: &mut Budget) -> bool {
+        if budget.tick() {
+            return false;
+        }
+        if depth == 0 {
+            return self.h(state, eo) == 0;
+        }
+        for r in &ALL_MOVES {
+            if !moves.is_empty() && prune_move(*moves.last().unwrap(), *r) {
+                continue;
+            }
+            for s in state.iter_mut() {
+                s.rotate(*r);
+            }
+            let mut neo = eo;
+            neo.rotate(*r);
+            if self.h(state, neo) < depth {
+                moves.push(*r);
+                if self.search(state, neo, depth - 1, moves, budget) {
+                    return true;
+                }
+                moves.pop();
+            }
+            for s in state.iter_mut() {
+                s.rotate(r.reverse());
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rubiks_cube::Rotation::*;
+
+    const SCRAMBLE: [Rotation; 20] = [F2, D, L, U2, B2, Lr, B2, L2, R, F2, Rr, D2, R2, F, D, U2, B, D, Rr, U2];
+
+    fn check(goal: Goal, max_depth: usize) -> usize {
+        let cube = CubeSequenceRepr::from(&SCRAMBLE);
+        assert!(!goal.is_solved(&cube));
+        let solver = GoalSolver::new(goal);
+        let solution = solver.solve(&cube, max_depth).unwrap();
+        assert!(solver.goal().is_solved(&cube.multiply(&CubeSequenceRepr::from(solution.moves()))));
+        solution.len()
+    }
+
+    #[test]
+    fn test_cross() {
+        assert!(check(Goal::cross(Face::D), 8) <= 8);
+        assert!(check(Goal::cross(Face::F), 8) <= 8);
+        let solver = GoalSolver::new(Goal::cross(Face::U));
+        assert_eq!(solver.solve(&CubeSequenceRepr::from(&[R, Ur]), 8).unwrap().len(), 2);
+        // no limit at all
+        assert_eq!(solver.solve(&CubeSequenceRepr::from(&[R, Ur]), usize::MAX).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_with_pieces() {
+        let goal = Goal::new().with_pieces(&[8, 9], &[4]).unwrap();
+        assert_eq!(goal.groups[0].edges, [8, 9]);
+        assert_eq!(Goal::new().with_pieces(&[0, 1, 2], &[0, 1]).unwrap_err(), GoalError::TooManyPieces(5));
+        assert_eq!(Goal::new().with_pieces(&[12], &[]).unwrap_err(), GoalError::NoSuchEdge(12));
+        assert_eq!(Goal::new().with_pieces(&[], &[8]).unwrap_err(), GoalError::NoSuchCorner(8));
+    }
+
+    #[test]
+    fn test_xcross_and_blocks() {
+        check(Goal::xcross(Face::D, 2).unwrap(), 11);
+        assert_eq!(Goal::xcross(Face::D, 4).unwrap_err(), GoalError::NoSuchSlot(4));
+        check(Goal::eoline(), 10);
+        check(Goal::first_block(), 10);
+    }
+}
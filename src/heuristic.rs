@@ -1,11 +1,13 @@
 use crate::rubiks_cube::*;
 use crate::num_traits::ToPrimitive;
 use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::fmt::{Display, Formatter};
 use std::marker::PhantomData;
-use crate::util::{encode_comb, encode_perm, comb};
+use crate::util::{encode_perm, comb};
 use std::ops::Index;
 
-/// Building heuristic tables
+// Building heuristic tables
 
 pub const ALL_MOVES: [Rotation; 18] = [
     Rotation::U, Rotation::D, Rotation::R, Rotation::L, Rotation::F, Rotation::B,
@@ -28,6 +30,35 @@ pub struct PruneTable<T: CubeRepr> {
     _phantom: PhantomData<T>,
 }
 
+// Saved tables: TABLE_MAGIC, the number of entries as a little-endian u32, then
+// one byte per entry (-1 for states the moves never reach)
+const TABLE_MAGIC: &[u8; 4] = b"CSPT";
+const TABLE_HEADER: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableError {
+    BadMagic,
+    // the header or the data is shorter than it should be, or there is data left over
+    BadLength,
+    // a table of a different size than the one asked for
+    WrongSize { expected: usize, found: usize },
+    // an entry that can't be a distance, and where it is
+    BadEntry(usize),
+}
+
+impl Display for TableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableError::BadMagic => write!(f, "not a pruning table"),
+            TableError::BadLength => write!(f, "table data has the wrong length"),
+            TableError::WrongSize { expected, found } => write!(f, "expected a table of {} entries, found {}", expected, found),
+            TableError::BadEntry(i) => write!(f, "invalid table entry at {}", i),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
 // Node data type used in PruneTable
 struct PruneNode<T: Sized> {
     state: T,
@@ -40,7 +71,7 @@ struct PruneNode<T: Sized> {
 pub fn prune_move(prev: Rotation, cur: Rotation) -> bool {
     let np = prev.normal().to_u8().unwrap();
     let nn = cur.normal().to_u8().unwrap();
-    np == nn || (np / 2 == nn / 2 && np > nn)
+    np == nn || (prev.axis() == cur.axis() && np > nn)
 }
 
 impl<T: CubeRepr + Copy> PruneTable<T> {
@@ -51,13 +82,55 @@ impl<T: CubeRepr + Copy> PruneTable<T> {
         }
     }
 
+    // a table saved with `to_bytes`, which has to hold `size` entries
+    pub fn from_bytes(bytes: &[u8], size: usize) -> Result<Self, TableError> {
+        if bytes.len() < TABLE_HEADER {
+            return Err(TableError::BadLength);
+        }
+        if &bytes[..4] != TABLE_MAGIC {
+            return Err(TableError::BadMagic);
+        }
+        let found = u32::from_le_bytes(bytes[4..TABLE_HEADER].try_into().unwrap()) as usize;
+        if found != size {
+            return Err(TableError::WrongSize { expected: size, found });
+        }
+        let data = &bytes[TABLE_HEADER..];
+        if data.len() != size {
+            return Err(TableError::BadLength);
+        }
+        let table: Vec<i8> = data.iter().map(|&b| b as i8).collect();
+        if let Some(i) = table.iter().position(|&d| d < -1) {
+            return Err(TableError::BadEntry(i));
+        }
+        Ok(PruneTable {
+            table,
+            _phantom: Default::default(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(TABLE_HEADER + self.table.len());
+        res.extend_from_slice(TABLE_MAGIC);
+        res.extend_from_slice(&(self.table.len() as u32).to_le_bytes());
+        res.extend(self.table.iter().map(|&d| d as u8));
+        res
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
     pub fn init<F>(&mut self, encoder: F, initial_states: &[T], valid_moves: &[Rotation])
         where F: Fn(&T) -> usize {
         let mut q = VecDeque::new();
         for s in initial_states.iter() {
-            self.table[encoder(&s)] = 0;
+            self.table[encoder(s)] = 0;
             for r in valid_moves.iter() {
-                let mut state = s.clone();
+                let mut state = *s;
                 state.rotate(*r);
                 q.push_back(PruneNode {
                     state,
@@ -74,7 +147,7 @@ impl<T: CubeRepr + Copy> PruneTable<T> {
                 self.table[idx] = depth;
                 for r in valid_moves.iter() {
                     if !prune_move(rot, *r) {
-                        let mut ns = state.clone();
+                        let mut ns = state;
                         ns.rotate(*r);
                         q.push_back(PruneNode {
                             state: ns,
@@ -96,20 +169,22 @@ impl<T: CubeRepr + Copy> Index<usize> for PruneTable<T> {
     }
 }
 
-// some special encoders
+// some special encoders; like the ones in rubiks_cube they expect the pieces of a
+// real cube, and give a meaningless index or panic for anything else, so they're
+// for the crate (the try_ versions check) and public for this release only.
+// This first one is the plain version of phase1_medge_encode_opt, kept to check
+// and time that against
+#[deprecated(note = "use `try_phase1_medge_encode`")]
 pub fn phase1_medge_encode(repr: &EdgePerm) -> usize {
     let mut buf = [0; 4];
-    let mut j = 0;
-    for i in 0..12 {
-        if repr.0[i] >= 4 && repr.0[i] < 8 {
-            buf[j] = i;
-            j += 1;
-        }
+    let slots = (0..12).filter(|&i| (4..8).contains(&repr.0[i]));
+    for (b, i) in buf.iter_mut().zip(slots) {
+        *b = i;
     }
-    assert_eq!(j, 4);
-    encode_comb(&buf, 12)
+    crate::util::encode_comb(&buf, 12)
 }
 
+#[deprecated(note = "use `try_phase1_medge_encode`")]
 pub fn phase1_medge_encode_opt(repr: &EdgePerm) -> usize {
     let mut k = 4;
     let mut res = 0;
@@ -126,56 +201,131 @@ pub fn phase1_medge_encode_opt(repr: &EdgePerm) -> usize {
     res
 }
 
+#[deprecated(note = "use `try_phase2_medge_encode`")]
 pub fn phase2_medge_encode(repr: &EdgePerm) -> usize {
     let mut buf = [0u8; 4];
-    for i in 4..8 {
-        buf[i - 4] = repr.0[i];
-    }
+    buf.copy_from_slice(&repr.0[4..8]);
     encode_perm(&buf)
 }
 
+#[deprecated(note = "use `try_phase2_udedge_encode`")]
 pub fn phase2_udedge_encode(repr: &EdgePerm) -> usize {
     let mut buf = [0u8; 8];
-    for i in 0..4 {
-        let v = repr.0[i];
-        buf[i] = if v < 4 { v } else { v - 4 };
-    }
-    for i in 8..12 {
-        let v = repr.0[i];
-        buf[i - 4] = if v < 4 { v } else { v - 4 };
+    for (b, &v) in buf.iter_mut().zip(repr.0[0..4].iter().chain(&repr.0[8..12])) {
+        *b = if v < 4 { v } else { v - 4 };
     }
     encode_perm(&buf)
 }
 
+// checked versions of the encoders above, None for input they can't handle
+pub fn try_phase1_medge_encode(repr: &EdgePerm) -> Option<usize> {
+    if repr.is_valid() { Some(phase1_medge_encode_opt(repr)) } else { None }
+}
+
+// the phase 2 encoders also need the E slice edges in the E slice
+fn in_phase2(repr: &EdgePerm) -> bool {
+    repr.is_valid() && repr.0[4..8].iter().all(|e| (4..8).contains(e))
+}
+
+pub fn try_phase2_medge_encode(repr: &EdgePerm) -> Option<usize> {
+    if in_phase2(repr) { Some(phase2_medge_encode(repr)) } else { None }
+}
+
+pub fn try_phase2_udedge_encode(repr: &EdgePerm) -> Option<usize> {
+    if in_phase2(repr) { Some(phase2_udedge_encode(repr)) } else { None }
+}
+
+// how each table below is built, public so the benchmarks in benches/ can time
+// exactly that
+pub fn phase1_edgeorient_table() -> PruneTable<EdgeOrient> {
+    let mut pt = PruneTable::new(2048);
+    pt.init(crate::rubiks_cube::eo_encode, &[EdgeOrient::new()], &ALL_MOVES);
+    pt
+}
+
+pub fn phase1_cornerorient_table() -> PruneTable<CornerOrient> {
+    let mut pt = PruneTable::new(2187);
+    pt.init(crate::rubiks_cube::co_encode, &[CornerOrient::new()], &ALL_MOVES);
+    pt
+}
+
+pub fn phase1_medge_table() -> PruneTable<EdgePerm> {
+    let mut pt = PruneTable::new(495);
+    pt.init(phase1_medge_encode_opt, &[EdgePerm::new()], &ALL_MOVES);
+    pt
+}
+
+pub fn phase2_udedge_table() -> PruneTable<EdgePerm> {
+    let mut pt = PruneTable::new(40320);
+    pt.init(phase2_udedge_encode, &[EdgePerm::new()], &PHASE2_MOVES);
+    pt
+}
+
+pub fn phase2_medge_table() -> PruneTable<EdgePerm> {
+    let mut pt = PruneTable::new(24);
+    pt.init(phase2_medge_encode, &[EdgePerm::new()], &PHASE2_MEDGE_MOVES);
+    pt
+}
+
+pub fn phase2_cornerperm_table() -> PruneTable<CornerPerm> {
+    let mut pt = PruneTable::new(40320);
+    pt.init(crate::rubiks_cube::cp_encode, &[CornerPerm::new()], &PHASE2_MOVES);
+    pt
+}
+
 lazy_static! {
-    pub static ref PHASE1_EDGEORIENT_PT: PruneTable<EdgeOrient> = {
-        let mut pt = PruneTable::new(2048);
-        pt.init(crate::rubiks_cube::eo_encode, &[EdgeOrient::new()], &ALL_MOVES);
-        pt
-    };
-    pub static ref PHASE1_CORNERORIENT_PT: PruneTable<CornerOrient> = {
-        let mut pt = PruneTable::new(2187);
-        pt.init(crate::rubiks_cube::co_encode, &[CornerOrient::new()], &ALL_MOVES);
-        pt
-    };
-    pub static ref PHASE1_MEDGE_PT: PruneTable<EdgePerm> = {
-        let mut pt = PruneTable::new(495);
-        pt.init(phase1_medge_encode_opt, &[EdgePerm::new()], &ALL_MOVES);
-        pt
-    };
-    pub static ref PHASE2_UDEDGE_PT: PruneTable<EdgePerm> = {
-        let mut pt = PruneTable::new(40320);
-        pt.init(phase2_udedge_encode, &[EdgePerm::new()], &PHASE2_MOVES);
-        pt
-    };
-    pub static ref PHASE2_MEDGE_PT: PruneTable<EdgePerm> = {
-        let mut pt = PruneTable::new(24);
-        pt.init(phase2_medge_encode, &[EdgePerm::new()], &PHASE2_MEDGE_MOVES);
-        pt
-    };
-    pub static ref PHASE2_CORNERPERM_PT: PruneTable<CornerPerm> = {
-        let mut pt = PruneTable::new(40320);
-        pt.init(crate::rubiks_cube::cp_encode, &[CornerPerm::new()], &PHASE2_MOVES);
-        pt
-    };
-}
\ No newline at end of file
+    pub static ref PHASE1_EDGEORIENT_PT: PruneTable<EdgeOrient> = phase1_edgeorient_table();
+    pub static ref PHASE1_CORNERORIENT_PT: PruneTable<CornerOrient> = phase1_cornerorient_table();
+    pub static ref PHASE1_MEDGE_PT: PruneTable<EdgePerm> = phase1_medge_table();
+    pub static ref PHASE2_UDEDGE_PT: PruneTable<EdgePerm> = phase2_udedge_table();
+    pub static ref PHASE2_MEDGE_PT: PruneTable<EdgePerm> = phase2_medge_table();
+    pub static ref PHASE2_CORNERPERM_PT: PruneTable<CornerPerm> = phase2_cornerperm_table();
+}
+// build all tables up front instead of on first use
+pub fn init_tables() {
+    lazy_static::initialize(&PHASE1_EDGEORIENT_PT);
+    lazy_static::initialize(&PHASE1_CORNERORIENT_PT);
+    lazy_static::initialize(&PHASE1_MEDGE_PT);
+    lazy_static::initialize(&PHASE2_UDEDGE_PT);
+    lazy_static::initialize(&PHASE2_MEDGE_PT);
+    lazy_static::initialize(&PHASE2_CORNERPERM_PT);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_table_bytes() {
+        let bytes = PHASE1_EDGEORIENT_PT.to_bytes();
+        let table: PruneTable<EdgeOrient> = PruneTable::from_bytes(&bytes, 2048).unwrap();
+        assert!((0..2048).all(|i| table[i] == PHASE1_EDGEORIENT_PT[i]));
+        assert_eq!(table.to_bytes(), bytes);
+
+        let load = |bytes: &[u8], size| PruneTable::<EdgeOrient>::from_bytes(bytes, size).err();
+        assert_eq!(load(&bytes, 2187), Some(TableError::WrongSize { expected: 2187, found: 2048 }));
+        assert_eq!(load(&bytes[..100], 2048), Some(TableError::BadLength));
+        assert_eq!(load(b"CSP", 0), Some(TableError::BadLength));
+        assert_eq!(load(b"XXXX\0\0\0\0", 0), Some(TableError::BadMagic));
+        let mut bad = bytes.clone();
+        bad[TABLE_HEADER + 5] = 0x80;
+        assert_eq!(load(&bad, 2048), Some(TableError::BadEntry(5)));
+    }
+
+    #[test]
+    fn test_try_encode() {
+        let ep = CubeSequenceRepr::from(&[Rotation::R, Rotation::U]).ep;
+        assert_eq!(try_phase1_medge_encode(&ep), Some(phase1_medge_encode(&ep)));
+        assert_eq!(try_phase2_medge_encode(&ep), None);
+        let ep = CubeSequenceRepr::from(&[Rotation::R2, Rotation::U]).ep;
+        assert_eq!(try_phase2_udedge_encode(&ep), Some(phase2_udedge_encode(&ep)));
+        assert_eq!(try_phase2_medge_encode(&ep), Some(phase2_medge_encode(&ep)));
+        let bad = EdgePerm([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 200]);
+        assert_eq!(try_phase1_medge_encode(&bad), None);
+        assert_eq!(try_phase2_udedge_encode(&bad), None);
+        // the unchecked ones don't panic either, whatever they're given
+        phase1_medge_encode(&bad);
+        phase1_medge_encode(&EdgePerm([4; 12]));
+        phase1_medge_encode(&EdgePerm([0; 12]));
+    }
+}
@@ -1,30 +1,160 @@
 use crate::rubiks_cube::{Rotation, EdgePerm, EdgeOrient, CornerOrient, CubeRepr, CornerPerm, eo_encode, co_encode, cp_encode, CubeSequenceRepr};
 use crate::heuristic::*;
-use std::process::exit;
+use crate::sequence::MoveSequence;
+use crate::solver::Budget;
+use std::time::{Duration, Instant};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-/// Kociemba algorithm
+#[derive(Debug, Copy, Clone)]
+pub struct SolverConfig {
+    // stop searching once a solution this short has been found
+    pub max_length: usize,
+    // give up after this long, keeping the best solution found so far
+    pub timeout: Option<Duration>,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        SolverConfig {
+            max_length: 22,
+            timeout: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Solution {
+    pub moves: MoveSequence,
+    // length of each phase (just one for single-phase searches), before
+    // cancelling moves where they meet
+    pub phases: Vec<usize>,
+    // search nodes visited and time spent over the whole solve
+    pub nodes: u64,
+    pub elapsed: Duration,
+    // where the nodes and time went; left empty by solvers that don't keep track
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stats: SearchStats,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SearchStats {
+    // nodes expanded at each depth, counted in moves from the start of the phase
+    pub phase1_nodes: Vec<u64>,
+    pub phase2_nodes: Vec<u64>,
+    // moves not tried because the heuristic put them past the depth limit
+    pub phase1_cutoffs: u64,
+    pub phase2_cutoffs: u64,
+    // phase 1 solutions handed on to phase 2
+    pub phase2_runs: u64,
+    pub phase1_time: Duration,
+    pub phase2_time: Duration,
+    // (time since the start, length) each time a shorter solution turned up
+    pub improvements: Vec<(Duration, usize)>,
+}
 
+// what the search is up to, for `KociembaSolver::solve_traced`
+#[derive(Debug, Copy, Clone)]
+pub enum SearchEvent<'a> {
+    // phase 1 starts over with a deeper limit
+    Phase1Depth(usize),
+    // a phase 1 solution, about to go through phase 2
+    Phase1Solution(&'a [Rotation]),
+    // a new best solution, and the time since the start
+    Improved(&'a MoveSequence, Duration),
+}
+
+// Kociemba algorithm
+//
+// The pruning tables are global and never change once built, so the solver
+// itself is only its config: every solve gets its own Search, and one solver
+// can be shared between threads (e.g. through an Arc) and solve concurrently.
+#[derive(Debug, Copy, Clone)]
 pub struct KociembaSolver {
+    config: SolverConfig,
+}
+
+// state of a single solve
+struct Search<'a> {
+    config: SolverConfig,
     initial: CubeSequenceRepr,
-    current_solve: Vec<Rotation>,
+    current_solve: Option<Solution>,
     phase1_moves: Vec<Rotation>,
     phase2_moves: Vec<Rotation>,
+    budget: Budget,
+    done: bool,
+    stats: SearchStats,
+    trace: &'a mut dyn FnMut(SearchEvent),
+}
+
+impl Default for KociembaSolver {
+    fn default() -> Self {
+        KociembaSolver::new()
+    }
 }
 
 impl KociembaSolver {
     pub fn new() -> Self {
-        KociembaSolver {
-            initial: CubeSequenceRepr::new(),
-            current_solve: vec![],
+        KociembaSolver::with_config(SolverConfig::default())
+    }
+
+    pub fn with_config(config: SolverConfig) -> Self {
+        KociembaSolver { config }
+    }
+
+    pub fn config(&self) -> &SolverConfig {
+        &self.config
+    }
+
+    // best solution found with the solver's own config, or None if the timeout
+    // hit before finding any; `Solver::solve` takes the config as an argument instead
+    pub fn solve_with_own_config(&self, scrambled: &CubeSequenceRepr) -> Option<Solution> {
+        self.solve_traced(scrambled, &mut |_| {})
+    }
+
+    // the same, telling `trace` about the search as it goes
+    pub fn solve_traced(&self, scrambled: &CubeSequenceRepr, trace: &mut dyn FnMut(SearchEvent)) -> Option<Solution> {
+        let mut search = Search {
+            config: self.config,
+            initial: *scrambled,
+            current_solve: None,
             phase1_moves: vec![],
             phase2_moves: vec![],
-        }
+            budget: Budget::new(self.config.timeout),
+            done: false,
+            stats: SearchStats::default(),
+            trace,
+        };
+        search.solve_phase1();
+        let mut solution = search.current_solve.take()?;
+        solution.nodes = search.budget.nodes;
+        solution.elapsed = search.budget.elapsed();
+        search.stats.phase1_time = solution.elapsed.saturating_sub(search.stats.phase2_time);
+        solution.stats = search.stats;
+        Some(solution)
+    }
+
+    // find a sequence taking `start` to `target`; this is the same as solving
+    // target^-1 * start, since whatever solves that takes start to target
+    pub fn solve_to(&self, start: &CubeSequenceRepr, target: &CubeSequenceRepr) -> Option<Solution> {
+        self.solve_with_own_config(&target.inverse().multiply(start))
     }
+}
 
-    pub fn solve(&mut self, scrambled: &CubeSequenceRepr) {
-        self.initial = scrambled.clone();
-        self.solve_phase1();
+// The lower bounds the search prunes with, for checking against known
+// distances: moves to reach G1 = <U, D, R2, L2, F2, B2>, and for a cube already
+// in G1, moves to solve it using only G1's moves
+pub fn phase1_bound(cube: &CubeSequenceRepr) -> usize {
+    Search::h1(&Phase1Repr::of(cube)) as usize
+}
+
+pub fn phase2_bound(cube: &CubeSequenceRepr) -> Option<usize> {
+    if !Phase1Repr::of(cube).ok() {
+        return None;
     }
+    Some(Search::h2(&Phase2Repr { ep: cube.ep, cp: cube.cp }) as usize)
 }
 
 #[derive(Copy, Clone)]
@@ -35,18 +165,14 @@ struct Phase1Repr {
 }
 
 impl Phase1Repr {
-    pub fn new() -> Self {
-        Phase1Repr {
-            eo: EdgeOrient::new(),
-            co: CornerOrient::new(),
-            ep: EdgePerm::new(),
-        }
+    fn of(cube: &CubeSequenceRepr) -> Self {
+        Phase1Repr { eo: cube.eo, co: cube.co, ep: cube.ep }
     }
 
     pub fn ok(&self) -> bool {
         !self.eo.0.contains(&true) &&
             !self.co.0.iter().any(|x| x > &0) &&
-            !self.ep.0[4..8].iter().any(|x| x < &4 || x >= &8)
+            self.ep.0[4..8].iter().all(|x| (4..8).contains(x))
     }
 }
 
@@ -65,13 +191,6 @@ struct Phase2Repr {
 }
 
 impl Phase2Repr {
-    pub fn new() -> Self {
-        Phase2Repr {
-            ep: EdgePerm::new(),
-            cp: CornerPerm::new(),
-        }
-    }
-
     pub fn ok(&self) -> bool {
         self.ep.0.iter().enumerate().all(|(x, y)| x == *y as usize) &&
             self.cp.0.iter().enumerate().all(|(x, y)| x == *y as usize)
@@ -85,8 +204,7 @@ impl CubeRepr for Phase2Repr {
     }
 }
 
-
-impl KociembaSolver {
+impl Search<'_> {
     fn h1(repr: &Phase1Repr) -> i8 {
         let h1 = PHASE1_EDGEORIENT_PT[eo_encode(&repr.eo)];
         let h2 = PHASE1_CORNERORIENT_PT[co_encode(&repr.co)];
@@ -102,36 +220,60 @@ impl KociembaSolver {
     }
 
     fn solve_phase1(&mut self) {
-        let repr = Phase1Repr {
-            eo: self.initial.eo,
-            co: self.initial.co,
-            ep: self.initial.ep,
-        };
-        let start_idx = KociembaSolver::h1(&repr);
+        let repr = Phase1Repr::of(&self.initial);
+        let start_idx = Search::h1(&repr);
         for i in start_idx..=12 {
-            // println!("Phase 1 searching depth {}", i);
+            // where the phases meet they cancel at most one move net, so
+            // deeper phase 1 solutions can't beat the current best
+            if self.current_solve.as_ref().is_some_and(|best| i as usize > best.moves.len()) {
+                return
+            }
+            (self.trace)(SearchEvent::Phase1Depth(i as usize));
             self.phase1_moves.clear();
-            self.search_phase1(repr.clone(), i);
+            self.search_phase1(repr, i);
+            if self.done {
+                return
+            }
+        }
+    }
+
+    // count a node, returns true once the search should stop
+    fn tick(&mut self) -> bool {
+        if self.budget.tick() {
+            self.done = true;
         }
+        self.done
     }
 
     fn search_phase1(&mut self, repr: Phase1Repr, depth: i8) {
+        count(&mut self.stats.phase1_nodes, self.phase1_moves.len());
+        if self.tick() {
+            return
+        }
         if repr.ok() {
-            // println!("Found phase 1 solution({}): {:?}", self.phase1_moves.len(), self.phase1_moves);
+            (self.trace)(SearchEvent::Phase1Solution(&self.phase1_moves));
+            let start = Instant::now();
+            self.stats.phase2_runs += 1;
             self.solve_phase2();
+            self.stats.phase2_time += start.elapsed();
             return
         }
         for r in &ALL_MOVES {
+            if self.done {
+                return
+            }
             if !self.phase1_moves.is_empty() &&
                 prune_move(*self.phase1_moves.last().unwrap(), *r) {
                 continue;
             }
-            let mut nr = repr.clone();
+            let mut nr = repr;
             nr.rotate(*r);
-            if KociembaSolver::h1(&nr) <= depth {
+            if Search::h1(&nr) <= depth {
                 self.phase1_moves.push(*r);
                 self.search_phase1(nr, depth - 1);
                 self.phase1_moves.pop();
+            } else {
+                self.stats.phase1_cutoffs += 1;
             }
         }
     }
@@ -147,29 +289,47 @@ impl KociembaSolver {
             }
             repr
         };
-        let start_depth = KociembaSolver::h2(&repr);
-        let max_depth = if self.current_solve.is_empty() {
-            18
-        } else {
-            self.current_solve.len() - self.phase1_moves.len() - 2
-        } as i8;
+        let start_depth = Search::h2(&repr);
+        // anything longer than this can't beat the current best; it goes
+        // negative, and nothing is searched, when phase 1 alone is too long
+        let max_depth = match &self.current_solve {
+            None => 18,
+            Some(solve) => solve.moves.len() as i8 - self.phase1_moves.len() as i8 - 2,
+        };
         for i in start_depth..=max_depth {
             self.phase2_moves.clear();
-            if self.search_phase2(repr.clone(), i) {
+            if self.search_phase2(repr, i) || self.done {
                 return
             }
         }
     }
 
     fn search_phase2(&mut self, repr: Phase2Repr, depth: i8) -> bool {
+        count(&mut self.stats.phase2_nodes, self.phase2_moves.len());
+        if self.tick() {
+            return false;
+        }
         if repr.ok() {
-            // print result
-            self.current_solve = self.phase1_moves.clone();
-            self.current_solve.extend(&self.phase2_moves);
-            println!("Found solution({}): {:?}", self.current_solve.len(), self.current_solve);
-            if self.current_solve.len() <= 22 {
-                exit(0);
+            // phase 1 and phase 2 may meet with cancellable moves
+            let mut solve = self.phase1_moves.clone();
+            solve.extend(&self.phase2_moves);
+            let moves = MoveSequence::from(solve).simplify();
+            if self.current_solve.as_ref().is_some_and(|best| moves.len() >= best.moves.len()) {
+                return true;
+            }
+            let elapsed = self.budget.elapsed();
+            (self.trace)(SearchEvent::Improved(&moves, elapsed));
+            self.stats.improvements.push((elapsed, moves.len()));
+            if moves.len() <= self.config.max_length {
+                self.done = true;
             }
+            self.current_solve = Some(Solution {
+                moves,
+                phases: vec![self.phase1_moves.len(), self.phase2_moves.len()],
+                nodes: 0,
+                elapsed: Duration::default(),
+                stats: SearchStats::default(),
+            });
             return true;
         }
         for r in &PHASE2_MOVES {
@@ -177,16 +337,107 @@ impl KociembaSolver {
                 prune_move(*self.phase2_moves.last().unwrap(), *r) {
                 continue
             }
-            let mut nc = repr.clone();
+            let mut nc = repr;
             nc.rotate(*r);
-            if KociembaSolver::h2(&nc) <= depth {
+            if Search::h2(&nc) <= depth {
                 self.phase2_moves.push(*r);
                 if self.search_phase2(nc, depth - 1) {
                     return true;
                 }
                 self.phase2_moves.pop();
+            } else {
+                self.stats.phase2_cutoffs += 1;
             }
         }
-        return false;
+        false
     }
-}
\ No newline at end of file
+}
+
+fn count(per_depth: &mut Vec<u64>, depth: usize) {
+    if per_depth.len() <= depth {
+        per_depth.resize(depth + 1, 0);
+    }
+    per_depth[depth] += 1;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rubiks_cube::Rotation::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_solve() {
+        let cube = CubeSequenceRepr::from(&[R, U, F2, Lr, D, B]);
+        let solution = KociembaSolver::new().solve_with_own_config(&cube).unwrap();
+        assert_eq!(cube.multiply(&CubeSequenceRepr::from(solution.moves.moves())), CubeSequenceRepr::new());
+        assert!(solution.moves.len() <= solution.phases.iter().sum());
+        assert!(solution.nodes > 0);
+    }
+
+    #[test]
+    fn test_solve_to() {
+        let start = CubeSequenceRepr::from(&[R, U, Fr]);
+        let target = CubeSequenceRepr::from(&[L2, D, B, Ur]);
+        let solver = KociembaSolver::new();
+        let solution = solver.solve_to(&start, &target).unwrap();
+        assert_eq!(start.multiply(&CubeSequenceRepr::from(solution.moves.moves())), target);
+        // solver state from the previous solve must not leak into the next one
+        let solution = solver.solve_to(&target, &start).unwrap();
+        assert_eq!(target.multiply(&CubeSequenceRepr::from(solution.moves.moves())), start);
+    }
+
+    #[test]
+    fn test_stats() {
+        let cube = CubeSequenceRepr::from(&[R, U, F2, Lr, D, B, R2, Fr, U2, L]);
+        let mut improved = 0;
+        let mut depths = vec![];
+        let solution = KociembaSolver::new().solve_traced(&cube, &mut |event| match event {
+            SearchEvent::Phase1Depth(d) => depths.push(d),
+            SearchEvent::Improved(..) => improved += 1,
+            SearchEvent::Phase1Solution(_) => {}
+        }).unwrap();
+        let stats = &solution.stats;
+        let total: u64 = stats.phase1_nodes.iter().chain(stats.phase2_nodes.iter()).sum();
+        assert_eq!(total, solution.nodes);
+        // the root comes up once per phase 1 iteration
+        assert_eq!(stats.phase1_nodes[0] as usize, depths.len());
+        assert!(stats.phase2_runs > 0 && stats.phase1_cutoffs > 0);
+        assert_eq!(stats.improvements.len(), improved);
+        assert_eq!(stats.improvements.last().unwrap().1, solution.moves.len());
+        assert!(depths.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_max_length_too_small() {
+        // no solution is ever short enough, so the search runs until the timeout
+        let solver = KociembaSolver::with_config(SolverConfig {
+            max_length: 1,
+            timeout: Some(Duration::from_millis(300)),
+        });
+        for moves in [vec![R, U], vec![F, Dr, L2], vec![B, R2, Ur, F2]] {
+            let cube = CubeSequenceRepr::from(moves.as_slice());
+            let solution = solver.solve_with_own_config(&cube).unwrap();
+            assert_eq!(cube.multiply(&CubeSequenceRepr::from(solution.moves.moves())), CubeSequenceRepr::new());
+            let lengths: Vec<_> = solution.stats.improvements.iter().map(|&(_, len)| len).collect();
+            assert!(lengths.windows(2).all(|w| w[0] > w[1]), "{:?}", lengths);
+            assert_eq!(*lengths.last().unwrap(), solution.moves.len());
+        }
+    }
+
+    #[test]
+    fn test_shared() {
+        let solver = Arc::new(KociembaSolver::new());
+        let scrambles = [vec![R, U, Fr, D2], vec![L, B2, Dr, F, U2], vec![F, R, Ur, Rr, Ur, R, U, Rr, Fr]];
+        let handles: Vec<_> = scrambles.iter().map(|moves| {
+            let solver = Arc::clone(&solver);
+            let cube = CubeSequenceRepr::from(moves.as_slice());
+            thread::spawn(move || (cube, solver.solve_with_own_config(&cube).unwrap()))
+        }).collect();
+        for h in handles {
+            let (cube, solution) = h.join().unwrap();
+            assert_eq!(cube.multiply(&CubeSequenceRepr::from(solution.moves.moves())), CubeSequenceRepr::new());
+        }
+    }
+}
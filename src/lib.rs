@@ -1,7 +1,30 @@
+// the old public names (piece fields, unchecked encoders, util) are deprecated
+// for users, not for the crate itself
+#![allow(deprecated)]
+
 pub mod rubiks_cube;
 pub mod kociemba;
 pub mod heuristic;
-mod util;
+pub mod analysis;
+pub mod sequence;
+pub mod goal;
+pub mod restricted;
+pub mod optimal;
+pub mod pocket;
+pub mod big_cube;
+pub mod thistlethwaite;
+pub mod solver;
+pub mod render;
+pub mod color;
+pub mod ffi;
+#[cfg(feature = "serde")]
+pub mod serialize;
+#[doc(hidden)]
+pub mod util;
+// the unchecked encoders, for benches/encoders.rs; not part of the API
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench;
 
 #[macro_use]
 extern crate num_derive;
@@ -0,0 +1,610 @@
+use crate::big_cube::{position, sticker};
+use crate::heuristic::{phase1_medge_encode_opt, prune_move, ALL_MOVES};
+use crate::restricted::{from_perm, inv, mul, to_perm, Perm, FACELETS, IDENTITY, POINTS};
+use crate::rubiks_cube::{co_encode, eo_encode, CornerOrient, CubeRepr, CubeSequenceRepr, EdgeOrient, EdgePerm, Rotation};
+use crate::sequence::MoveSequence;
+use crate::solver::Budget;
+
+// Optimal solving: the fewest face turns for any state, after Kociemba's optimal solver
+//
+// Any solution takes the cube into G1 = <U, D, R2, L2, F2, B2> on the way, and
+// into the same subgroup about the R/L and F/B axes, so the moves needed to
+// reach each of those bound the distance from below; IDA* prunes with the
+// largest of the three. The table has the exact number of moves into G1 for
+// every (edge flip, E slice, corner twist) coordinate, as phase 1 of the
+// two-phase search sees the cube, up to the 16 symmetries that keep the U/D
+// axis: 64430 classes of (flip, slice) pairs times 2187 twists, 4 bits each.
+// That is about 70MB, and 15 seconds or so to build in release mode.
+//
+// Each move of distance costs about 13 times the search of the one before: a
+// state 15 moves from solved takes seconds, random states (mostly 17 or 18
+// moves) anything from minutes to hours, and superflip, whose symmetries cut
+// the search down a long way (see Search::search), about 40 minutes.
+
+const FLIPS: usize = 2048;
+const SLICES: usize = 495;
+const TWISTS: usize = 2187;
+const SYMMETRIES: usize = 16;
+const FLIPSLICE_CLASSES: usize = 64430;
+const DISTANCE_ENTRIES: usize = FLIPSLICE_CLASSES * TWISTS;
+
+// a Tables::distances entry not reached yet
+const UNSET: u8 = 0xf;
+
+// where a cube is as far as the table is concerned
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Coords {
+    flip: u16,
+    slice: u16,
+    twist: u16,
+}
+
+impl Coords {
+    fn of(cube: &CubeSequenceRepr) -> Self {
+        Coords {
+            flip: eo_encode(&cube.eo) as u16,
+            slice: phase1_medge_encode_opt(&cube.ep) as u16,
+            twist: co_encode(&cube.co) as u16,
+        }
+    }
+
+    fn flipslice(&self) -> usize {
+        self.slice as usize * FLIPS + self.flip as usize
+    }
+}
+
+struct Tables {
+    // each coordinate after each move, in Rotation order
+    flip_move: Vec<[u16; 18]>,
+    slice_move: Vec<[u16; 18]>,
+    twist_move: Vec<[u16; 18]>,
+    // each twist seen through each symmetry
+    twist_conj: Vec<[u16; SYMMETRIES]>,
+    // for each (flip, slice) pair, by Coords::flipslice: its class times 16,
+    // plus the symmetry that takes it to the class representative
+    class: Vec<u32>,
+    reps: Vec<u32>,
+    // for each class, which symmetries leave its representative as it is; a
+    // state then has an entry for each twist those take it to
+    rep_syms: Vec<u16>,
+    // all 48 symmetries of the cube: the 16 above after turning R/L, F/B or
+    // U/D (that is, nothing) onto the U/D axis, so the first on each axis is
+    // just that turn; and each move seen through each of them
+    all_syms: Vec<Perm>,
+    sym_moves: Vec<[u8; 18]>,
+    // two entries to a byte, the even one in the low bits
+    distances: Vec<u8>,
+}
+
+lazy_static! {
+    static ref TABLES: Tables = Tables::new();
+}
+
+// a whole-cube rotation or reflection, as a map of the doubled coordinates in
+// big_cube.rs, and what it does to the 54 points
+fn transform<F: Fn([i32; 3]) -> [i32; 3]>(f: F) -> Perm {
+    let mut res = [0u8; POINTS];
+    for (i, &(face, r, c)) in FACELETS.iter().enumerate() {
+        let to = sticker(f(position(face, r, c, 3)), 3);
+        res[i] = FACELETS.iter().position(|&x| x == to).unwrap() as u8;
+    }
+    res
+}
+
+// the 16 symmetries that keep U/D on the U/D axis: k quarter turns of the whole
+// cube about U/D, then possibly turning it upside down about F/B, then possibly
+// reflecting R onto L; 0 is the identity
+fn symmetry(k: usize) -> Perm {
+    transform(|pos| {
+        let mut p = pos;
+        for _ in 0..k % 4 {
+            p = [-p[2], p[1], p[0]];
+        }
+        if k / 4 % 2 == 1 {
+            p = [-p[0], -p[1], p[2]];
+        }
+        if k / 8 == 1 {
+            p = [-p[0], p[1], p[2]];
+        }
+        p
+    })
+}
+
+// `cube` seen through `sym`; the map respects multiplication, so it sends face
+// turns to face turns and solutions to solutions of the same length
+fn conjugate(cube: &CubeSequenceRepr, sym: &Perm) -> CubeSequenceRepr {
+    from_perm(&mul(&mul(&inv(sym), &to_perm(cube)), sym))
+}
+
+// a cube for each value of the coordinates, with everything else solved
+fn flip_cube(flip: usize) -> CubeSequenceRepr {
+    let mut res = CubeSequenceRepr::new();
+    for i in 0..11 {
+        res.eo.0[i] = (flip >> (10 - i)) & 1 == 1;
+    }
+    res.eo.0[11] = res.eo.0[..11].iter().fold(false, |acc, &x| acc ^ x);
+    res
+}
+
+fn twist_cube(twist: usize) -> CubeSequenceRepr {
+    let mut res = CubeSequenceRepr::new();
+    let mut t = twist;
+    for i in (0..7).rev() {
+        res.co.0[i] = (t % 3) as u8;
+        t /= 3;
+    }
+    res.co.0[7] = (3 - res.co.0[..7].iter().sum::<u8>() % 3) % 3;
+    res
+}
+
+// E slice edges where the bits of `mask` are, in order, the others in order
+// around them
+fn slice_perm(mask: usize) -> EdgePerm {
+    let mut ep = EdgePerm::new();
+    let (mut e, mut other) = (4..8, [0, 1, 2, 3, 8, 9, 10, 11].iter());
+    for (i, p) in ep.0.iter_mut().enumerate() {
+        *p = if mask >> i & 1 == 1 { e.next().unwrap() } else { *other.next().unwrap() };
+    }
+    ep
+}
+
+fn move_table<T: CubeRepr + Copy, F: Fn(&T) -> usize>(states: &[T], encode: F) -> Vec<[u16; 18]> {
+    states.iter().map(|s| {
+        let mut res = [0; 18];
+        for (r, to) in ALL_MOVES.iter().zip(res.iter_mut()) {
+            let mut next = *s;
+            next.rotate(*r);
+            *to = encode(&next) as u16;
+        }
+        res
+    }).collect()
+}
+
+impl Tables {
+    fn new() -> Self {
+        let flips: Vec<CubeSequenceRepr> = (0..FLIPS).map(flip_cube).collect();
+        let twists: Vec<CubeSequenceRepr> = (0..TWISTS).map(twist_cube).collect();
+        let mut slices = vec![EdgePerm::new(); SLICES];
+        for mask in (0..1 << 12).filter(|m: &usize| m.count_ones() == 4) {
+            let ep = slice_perm(mask);
+            slices[phase1_medge_encode_opt(&ep)] = ep;
+        }
+        let syms: Vec<Perm> = (0..SYMMETRIES).map(symmetry).collect();
+        let inverse: Vec<usize> = syms.iter()
+            .map(|s| syms.iter().position(|t| mul(s, t) == IDENTITY).unwrap())
+            .collect();
+
+        let eo: Vec<EdgeOrient> = flips.iter().map(|c| c.eo).collect();
+        let co: Vec<CornerOrient> = twists.iter().map(|c| c.co).collect();
+        let twist_conj = twists.iter()
+            .map(|c| {
+                let mut res = [0; SYMMETRIES];
+                for (s, to) in syms.iter().zip(res.iter_mut()) {
+                    *to = co_encode(&conjugate(c, s).co) as u16;
+                }
+                res
+            })
+            .collect();
+
+        let mut class = vec![u32::MAX; FLIPS * SLICES];
+        let mut reps = Vec::with_capacity(FLIPSLICE_CLASSES);
+        let mut rep_syms = Vec::with_capacity(FLIPSLICE_CLASSES);
+        for fs in 0..FLIPS * SLICES {
+            if class[fs] != u32::MAX {
+                continue;
+            }
+            let mut cube = flips[fs % FLIPS];
+            cube.ep = slices[fs / FLIPS];
+            let mut fixed = 0;
+            for (s, sym) in syms.iter().enumerate() {
+                let other = Coords::of(&conjugate(&cube, sym)).flipslice();
+                if other == fs {
+                    fixed |= 1 << s;
+                }
+                if class[other] == u32::MAX {
+                    class[other] = (reps.len() * SYMMETRIES + inverse[s]) as u32;
+                }
+            }
+            reps.push(fs as u32);
+            rep_syms.push(fixed);
+        }
+        assert_eq!(reps.len(), FLIPSLICE_CLASSES);
+
+        let axes = [
+            IDENTITY,
+            // R to U, and F to U
+            transform(|[x, y, z]| [-y, x, z]),
+            transform(|[x, y, z]| [x, z, -y]),
+        ];
+        let all_syms: Vec<Perm> = axes.iter().flat_map(|a| syms.iter().map(move |s| mul(a, s))).collect();
+        let sym_moves = all_syms.iter()
+            .map(|sym| {
+                let mut res = [0; 18];
+                for (r, to) in ALL_MOVES.iter().zip(res.iter_mut()) {
+                    let turned = conjugate(&CubeSequenceRepr::from(&[*r]), sym);
+                    *to = ALL_MOVES.iter().position(|&m| CubeSequenceRepr::from(&[m]) == turned).unwrap() as u8;
+                }
+                res
+            })
+            .collect();
+
+        let mut res = Tables {
+            flip_move: move_table(&eo, eo_encode),
+            slice_move: move_table(&slices, phase1_medge_encode_opt),
+            twist_move: move_table(&co, co_encode),
+            twist_conj,
+            class,
+            reps,
+            rep_syms,
+            all_syms,
+            sym_moves,
+            distances: vec![],
+        };
+        res.fill_distances();
+        res
+    }
+
+    fn index(&self, c: Coords) -> usize {
+        let class = self.class[c.flipslice()] as usize;
+        class / SYMMETRIES * TWISTS + self.twist_conj[c.twist as usize][class % SYMMETRIES] as usize
+    }
+
+    fn get(&self, i: usize) -> u8 {
+        (self.distances[i / 2] >> (i % 2 * 4)) & 0xf
+    }
+
+    fn set(&mut self, i: usize, d: u8) {
+        let shift = i % 2 * 4;
+        self.distances[i / 2] = (self.distances[i / 2] & !(0xf << shift)) | (d << shift);
+    }
+
+    // after ALL_MOVES[r]
+    fn turn(&self, c: Coords, r: usize) -> Coords {
+        Coords {
+            flip: self.flip_move[c.flip as usize][r],
+            slice: self.slice_move[c.slice as usize][r],
+            twist: self.twist_move[c.twist as usize][r],
+        }
+    }
+
+    // the state an entry stands for
+    fn entry(&self, i: usize) -> Coords {
+        let fs = self.reps[i / TWISTS] as usize;
+        Coords { flip: (fs % FLIPS) as u16, slice: (fs / FLIPS) as u16, twist: (i % TWISTS) as u16 }
+    }
+
+    // set entry `i` and the others for the same state, returning how many were unset
+    fn set_all(&mut self, i: usize, d: u8) -> usize {
+        let (class, twist) = (i / TWISTS, i % TWISTS);
+        let fixed = self.rep_syms[class];
+        let mut res = 0;
+        for s in (0..SYMMETRIES).filter(|s| fixed >> s & 1 == 1) {
+            let j = class * TWISTS + self.twist_conj[twist][s] as usize;
+            if self.get(j) == UNSET {
+                self.set(j, d);
+                res += 1;
+            }
+        }
+        res
+    }
+
+    // breadth first from G1; once most entries are done, it's quicker to look
+    // from each entry left for one a move away than the other way round
+    fn fill_distances(&mut self) {
+        self.distances = vec![0xff; (DISTANCE_ENTRIES + 1) / 2];
+        let start = self.index(Coords::of(&CubeSequenceRepr::new()));
+        let mut filled = self.set_all(start, 0);
+        let mut depth = 0;
+        while filled < DISTANCE_ENTRIES {
+            let backward = filled > DISTANCE_ENTRIES / 3;
+            for i in 0..DISTANCE_ENTRIES {
+                let d = self.get(i);
+                if backward && d == UNSET {
+                    let c = self.entry(i);
+                    if (0..18).any(|r| self.get(self.index(self.turn(c, r))) == depth) {
+                        filled += self.set_all(i, depth + 1);
+                    }
+                } else if !backward && d == depth {
+                    let c = self.entry(i);
+                    for r in 0..18 {
+                        let j = self.index(self.turn(c, r));
+                        if self.get(j) == UNSET {
+                            filled += self.set_all(j, depth + 1);
+                        }
+                    }
+                }
+            }
+            depth += 1;
+        }
+    }
+
+    // moves into G1 for a cube at `c`
+    fn distance(&self, c: Coords) -> u8 {
+        self.get(self.index(c))
+    }
+
+    // the cube on each axis, turned so the axis is U/D
+    fn axis_coords(&self, cube: &CubeSequenceRepr) -> [Coords; 3] {
+        [0, 1, 2].map(|k| Coords::of(&conjugate(cube, &self.all_syms[k * SYMMETRIES])))
+    }
+
+    fn bound(&self, axes: &[Coords; 3]) -> u8 {
+        combine(axes.map(|c| self.distance(c)))
+    }
+}
+
+// the largest of the three distances into G1, and one more when they're all the
+// same and not 0: a solution that short would bring all three down with every
+// move, but the cube a move from solved is a face turn, already in G1 on its axis
+fn combine(d: [u8; 3]) -> u8 {
+    let max = d[0].max(d[1]).max(d[2]);
+    if max > 0 && d[0] == d[1] && d[1] == d[2] {
+        max + 1
+    } else {
+        max
+    }
+}
+
+// how many moves `cube` needs at least, the bound the search prunes with
+pub fn lower_bound(cube: &CubeSequenceRepr) -> usize {
+    TABLES.bound(&TABLES.axis_coords(cube)) as usize
+}
+
+pub struct OptimalSolver {}
+
+impl Default for OptimalSolver {
+    fn default() -> Self {
+        OptimalSolver::new()
+    }
+}
+
+impl OptimalSolver {
+    // the tables are built on the first solve, once for all solvers, and that
+    // solve's timeout counts the time it takes
+    pub fn new() -> Self {
+        OptimalSolver {}
+    }
+
+    // build the tables up front instead of on first use
+    pub fn init_tables() {
+        lazy_static::initialize(&TABLES);
+    }
+
+    // a shortest solution, or None if that's longer than `max_depth` or the
+    // budget runs out first
+    pub(crate) fn solve_within(&self, cube: &CubeSequenceRepr, max_depth: usize, budget: &mut Budget) -> Option<MoveSequence> {
+        let tables: &Tables = &TABLES;
+        let axes = tables.axis_coords(cube);
+        let symmetric = bits(ALL_SYMMETRIES)
+            .filter(|&s| conjugate(cube, &tables.all_syms[s]) == *cube)
+            .fold(0, |acc, s| acc | 1 << s);
+        let mut search = Search { tables, cube, moves: vec![], budget };
+        for depth in tables.bound(&axes) as usize..=max_depth {
+            if search.search(axes, symmetric, true, depth as u8) {
+                return Some(MoveSequence::from(search.moves));
+            }
+            if search.budget.expired {
+                break;
+            }
+        }
+        None
+    }
+}
+
+const ALL_SYMMETRIES: u64 = (1 << (3 * SYMMETRIES)) - 1;
+
+// the bits set in `mask`, lowest first
+fn bits(mut mask: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        let i = mask.trailing_zeros() as usize;
+        mask &= mask.wrapping_sub(1);
+        (i < 64).then_some(i)
+    })
+}
+
+struct Search<'a> {
+    tables: &'a Tables,
+    cube: &'a CubeSequenceRepr,
+    moves: Vec<Rotation>,
+    budget: &'a mut Budget,
+}
+
+impl<'a> Search<'a> {
+    // whether the cube can be solved in exactly `depth` more moves after
+    // `self.moves`; the bound at `axes` is at most `depth`. `symmetric` has
+    // the symmetries that leave both the cube and those moves as they are:
+    // they take solutions to solutions, so of the moves they swap around only
+    // the first needs trying. Only very symmetric cubes (superflip, say) have
+    // any besides the identity, and those are the slowest to solve.
+    //
+    // A symmetry can swap the faces of an axis, so a solution starting R' L
+    // may only turn up as L R', which prune_move rules out; `ordered` is
+    // false after a move picked that way, leaving only the same face out
+    fn search(&mut self, axes: [Coords; 3], symmetric: u64, ordered: bool, depth: u8) -> bool {
+        if self.budget.tick() {
+            return false;
+        }
+        if depth == 0 {
+            // in G1 on all three axes, which is rare enough to check the long way
+            return self.cube.multiply(&CubeSequenceRepr::from(&self.moves)) == CubeSequenceRepr::new();
+        }
+        let prev = self.moves.last().copied();
+        let mut open = 0;
+        for (i, &r) in ALL_MOVES.iter().enumerate() {
+            let pruned = prev.is_some_and(|p| if ordered { prune_move(p, r) } else { p.normal() == r.normal() });
+            if !pruned && (symmetric == 1 || bits(symmetric).all(|s| self.tables.sym_moves[s][i] as usize >= i)) {
+                open |= 1 << i;
+            }
+        }
+        // one axis at a time for all the moves, so the table lookups, which
+        // mostly miss the cache, go out together rather than one after another
+        let mut next = [axes; 18];
+        let mut dist = [[0; 3]; 18];
+        for k in 0..3 {
+            let mut index = [0; 18];
+            for i in bits(open) {
+                next[i][k] = self.tables.turn(axes[k], self.tables.sym_moves[k * SYMMETRIES][i] as usize);
+                index[i] = self.tables.index(next[i][k]);
+            }
+            for i in bits(open) {
+                dist[i][k] = self.tables.get(index[i]);
+                if dist[i][k] >= depth {
+                    open &= !(1 << i);
+                }
+            }
+        }
+        for i in bits(open) {
+            if combine(dist[i]) >= depth {
+                continue;
+            }
+            let child = if symmetric == 1 {
+                1
+            } else {
+                bits(symmetric).filter(|&s| self.tables.sym_moves[s][i] as usize == i).fold(0, |acc, s| acc | 1 << s)
+            };
+            self.moves.push(ALL_MOVES[i]);
+            if self.search(next[i], child, symmetric == 1, depth - 1) {
+                return true;
+            }
+            self.moves.pop();
+            if self.budget.expired {
+                return false;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::kociemba::{KociembaSolver, SearchEvent, SolverConfig};
+    use crate::restricted::RestrictedSolver;
+    use crate::rubiks_cube::Rotation::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_symmetries() {
+        let syms: Vec<Perm> = (0..SYMMETRIES).map(symmetry).collect();
+        assert_eq!(syms[0], IDENTITY);
+        for (i, s) in syms.iter().enumerate() {
+            assert!(!syms[..i].contains(s));
+            // a group: closed under composition
+            assert!(syms.iter().all(|t| syms.contains(&mul(s, t))));
+        }
+        // turning the cube over sends U to D, the reflection R to L
+        let mut rng = StdRng::seed_from_u64(42);
+        let u = CubeSequenceRepr::from(&[U]);
+        assert_eq!(conjugate(&u, &syms[4]), CubeSequenceRepr::from(&[D]));
+        assert_eq!(conjugate(&CubeSequenceRepr::from(&[R]), &syms[8]), CubeSequenceRepr::from(&[Lr]));
+        for _ in 0..20 {
+            let a = CubeSequenceRepr::random(&mut rng);
+            let b = CubeSequenceRepr::random(&mut rng);
+            for s in syms.iter() {
+                let (ca, cb) = (conjugate(&a, s), conjugate(&b, s));
+                assert!(ca.validate().is_ok());
+                assert_eq!(conjugate(&a.multiply(&b), s), ca.multiply(&cb));
+            }
+        }
+        // and all 48 on the three axes, every one of which leaves superflip alone
+        let mut superflip = CubeSequenceRepr::new();
+        superflip.eo = EdgeOrient([true; 12]);
+        for (i, s) in TABLES.all_syms.iter().enumerate() {
+            assert!(!TABLES.all_syms[..i].contains(s));
+            assert_eq!(conjugate(&superflip, s), superflip);
+        }
+    }
+
+    #[test]
+    fn test_coords() {
+        // the move tables follow the cube on every axis
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut cube = CubeSequenceRepr::new();
+        let mut axes = TABLES.axis_coords(&cube);
+        for _ in 0..100 {
+            let r = rng.gen_range(0..18);
+            cube.rotate(ALL_MOVES[r]);
+            for (k, c) in axes.iter_mut().enumerate() {
+                *c = TABLES.turn(*c, TABLES.sym_moves[k * SYMMETRIES][r] as usize);
+            }
+            assert_eq!(axes, TABLES.axis_coords(&cube));
+        }
+        // and symmetric states share an entry, or a distance where the class
+        // representative is symmetric itself, as it is with only corners twisted
+        let syms: Vec<Perm> = (0..SYMMETRIES).map(symmetry).collect();
+        let mut twisted = CubeSequenceRepr::new();
+        twisted.co = cube.co;
+        for s in syms.iter() {
+            let i = TABLES.index(Coords::of(&cube));
+            assert_eq!(TABLES.index(Coords::of(&conjugate(&cube, s))), i);
+            let d = TABLES.distance(Coords::of(&twisted));
+            assert_eq!(TABLES.distance(Coords::of(&conjugate(&twisted, s))), d);
+        }
+    }
+
+    #[test]
+    fn test_distances() {
+        let mut counts = [0; 16];
+        for i in 0..DISTANCE_ENTRIES {
+            counts[TABLES.get(i) as usize] += 1;
+        }
+        // nothing left unset, and phase 1 never takes more than 12 moves; the
+        // same whether filled forwards or backwards from depth 10 on
+        assert_eq!(counts, [
+            1, 1, 5, 44, 487, 5841, 68364, 776568, 7950748, 52098876, 76236234, 3771112, 129, 0, 0, 0,
+        ]);
+        // the same as the first phase 1 solution the two-phase search finds
+        let mut rng = StdRng::seed_from_u64(3);
+        let solver = KociembaSolver::with_config(SolverConfig { max_length: 30, timeout: None });
+        for _ in 0..5 {
+            let cube = CubeSequenceRepr::random(&mut rng);
+            let mut first = None;
+            solver.solve_traced(&cube, &mut |event| {
+                if let SearchEvent::Phase1Solution(moves) = event {
+                    first.get_or_insert(moves.len());
+                }
+            });
+            assert_eq!(Some(TABLES.distance(Coords::of(&cube)) as usize), first);
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let solver = OptimalSolver::new();
+        let solve = |moves: &[Rotation]| {
+            let cube = CubeSequenceRepr::from(moves);
+            let solution = solver.solve_within(&cube, 20, &mut Budget::new(None)).unwrap();
+            assert_eq!(cube.multiply(&CubeSequenceRepr::from(solution.moves())), CubeSequenceRepr::new());
+            solution.len()
+        };
+        assert_eq!(solve(&[]), 0);
+        assert_eq!(solve(&[R, U, Rr, Ur]), 4);
+        // symmetric states, where the search skips moves the symmetries swap;
+        // the mirror image of L' R is R L', its only solutions R' L and L R'
+        assert_eq!(solve(&[Lr, R]), 2);
+        assert_eq!(solve(&[U2, D2, F2, B2, L2, R2]), 6);
+        assert_eq!(solve(&[U, Dr, R, Lr, F, Br, U, Dr]), 8);
+        assert_eq!(solve(&[F2, B2, U, Dr, R2, L2, U, Dr]), 8);
+        // as short as the brute force search over all face turns finds, for
+        // everything up to 3 moves (plenty of it symmetric) and a few longer
+        let all = RestrictedSolver::new(&ALL_MOVES);
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut sequences: Vec<Vec<Rotation>> = vec![vec![]];
+        let mut last = sequences.clone();
+        for _ in 0..3 {
+            last = last.iter()
+                .flat_map(|s| ALL_MOVES.iter().map(move |&r| [s.as_slice(), &[r]].concat()))
+                .collect();
+            sequences.extend(last.iter().cloned());
+        }
+        sequences.extend((0..5).map(|_| (0..6).map(|_| ALL_MOVES[rng.gen_range(0..18)]).collect()));
+        for moves in sequences.iter() {
+            let cube = CubeSequenceRepr::from(moves);
+            assert_eq!(Some(solve(moves)), all.solve(&cube, 6).map(|s| s.len()), "{:?}", moves);
+        }
+        let cube = CubeSequenceRepr::from(&[R, U, F]);
+        assert!(lower_bound(&cube) <= 3);
+        assert_eq!(solver.solve_within(&cube, 2, &mut Budget::new(None)), None);
+    }
+}
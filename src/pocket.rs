@@ -0,0 +1,294 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::heuristic::{prune_move, PruneTable, ALL_MOVES};
+use crate::rubiks_cube::{CornerOrient, CornerPerm, CubeRepr, CubeSequenceRepr, Rotation};
+use crate::sequence::MoveSequence;
+use crate::util::encode_perm;
+
+// 2x2x2 (Pocket Cube)
+//
+// A 2x2 is just the corners of a 3x3, but without centres any state can be
+// held in 24 ways. PocketCube fixes the DBL corner in place, which leaves
+// 7! * 3^6 = 3674160 states, all of them reachable with U, R and F alone;
+// DISTANCE_TABLE holds the exact distance to solved for every one of them.
+
+pub const POCKET_MOVES: [Rotation; 9] = [
+    Rotation::U, Rotation::Ur, Rotation::U2,
+    Rotation::R, Rotation::Rr, Rotation::R2,
+    Rotation::F, Rotation::Fr, Rotation::F2,
+];
+
+pub const POCKET_STATES: usize = 3674160;
+
+// the corner that stays put
+const FIXED: usize = 4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+// like CubeSequenceRepr, a real cube unless the deprecated fields are written
+pub struct PocketCube {
+    #[deprecated(note = "read it with `cp()`")]
+    pub cp: CornerPerm,
+    #[deprecated(note = "read it with `co()`")]
+    pub co: CornerOrient,
+}
+
+// D, L and B turns move DBL, so the cube is then turned as a whole to put it
+// back; D is U with the cube held differently, and so on
+impl CubeRepr for PocketCube {
+    fn rotate(&mut self, r: Rotation) {
+        self.cp.rotate(r);
+        self.co.rotate(r);
+        if self.cp.0[FIXED] as usize != FIXED || self.co.0[FIXED] != 0 {
+            let mut cube = CubeSequenceRepr::new();
+            cube.cp = self.cp;
+            cube.co = self.co;
+            *self = PocketCube::from_cube(&cube);
+        }
+    }
+}
+
+impl Default for PocketCube {
+    fn default() -> Self {
+        PocketCube::new()
+    }
+}
+
+lazy_static! {
+    // whole-cube rotations, as what they do to the corners: x = R L', y = U D', z = F B'
+    static ref CUBE_ROTATIONS: Vec<CubeSequenceRepr> = {
+        let gens = [
+            CubeSequenceRepr::from(&[Rotation::R, Rotation::Lr]),
+            CubeSequenceRepr::from(&[Rotation::U, Rotation::Dr]),
+            CubeSequenceRepr::from(&[Rotation::F, Rotation::Br]),
+        ];
+        let mut res = vec![CubeSequenceRepr::new()];
+        let mut i = 0;
+        while i < res.len() {
+            for g in gens.iter() {
+                let next = res[i].multiply(g);
+                if !res.iter().any(|r| same_corners(r, &next)) {
+                    res.push(next);
+                }
+            }
+            i += 1;
+        }
+        assert_eq!(res.len(), 24);
+        res
+    };
+
+    pub static ref DISTANCE_TABLE: PruneTable<PocketCube> = distance_table();
+}
+
+// how DISTANCE_TABLE is built, public so the benchmarks in benches/ can time it
+pub fn distance_table() -> PruneTable<PocketCube> {
+    let mut pt = PruneTable::new(POCKET_STATES);
+    pt.init(pocket_encode, &[PocketCube::new()], &POCKET_MOVES);
+    pt
+}
+
+fn same_corners(a: &CubeSequenceRepr, b: &CubeSequenceRepr) -> bool {
+    a.cp == b.cp && a.co == b.co
+}
+
+impl PocketCube {
+    pub fn new() -> Self {
+        PocketCube { cp: CornerPerm::new(), co: CornerOrient::new() }
+    }
+
+    // the corners of `cube`, turned as a whole so that DBL is back in place
+    pub fn from_cube(cube: &CubeSequenceRepr) -> Self {
+        let (res, _) = PocketCube::normalize(cube);
+        res
+    }
+
+    // and the rotation (into CUBE_ROTATIONS) that did it
+    fn normalize(cube: &CubeSequenceRepr) -> (Self, usize) {
+        CUBE_ROTATIONS.iter().enumerate()
+            .map(|(i, r)| (cube.multiply(r), i))
+            .find(|(c, _)| c.cp.0[FIXED] as usize == FIXED && c.co.0[FIXED] == 0)
+            .map(|(c, i)| (PocketCube { cp: c.cp, co: c.co }, i))
+            .unwrap()
+    }
+
+    // uniformly random among all states
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut res = PocketCube::new();
+        let mut pieces = [0u8, 1, 2, 3, 5, 6, 7];
+        pieces.shuffle(rng);
+        for (&pos, &p) in [0, 1, 2, 3, 5, 6, 7].iter().zip(pieces.iter()) {
+            res.cp.0[pos] = p;
+        }
+        for &pos in [0, 1, 2, 3, 5, 6].iter() {
+            res.co.0[pos] = rng.gen_range(0..3);
+        }
+        res.co.0[7] = (3 - res.co.0.iter().sum::<u8>() % 3) % 3;
+        res
+    }
+
+    pub fn cp(&self) -> &CornerPerm {
+        &self.cp
+    }
+
+    pub fn co(&self) -> &CornerOrient {
+        &self.co
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == PocketCube::new()
+    }
+
+    // moves needed to solve it, from DISTANCE_TABLE
+    pub fn distance(&self) -> usize {
+        DISTANCE_TABLE[pocket_encode(self)] as usize
+    }
+}
+
+// permutation of the 7 free corners, then orientation of the 6 free corners that
+// don't follow from the rest
+pub fn pocket_encode(repr: &PocketCube) -> usize {
+    let mut perm = [0u8; 7];
+    for (k, &pos) in [0, 1, 2, 3, 5, 6, 7].iter().enumerate() {
+        let p = repr.cp.0[pos];
+        perm[k] = if p as usize > FIXED { p - 1 } else { p };
+    }
+    let mut orient = 0;
+    for &pos in [0, 1, 2, 3, 5, 6].iter() {
+        orient = orient * 3 + repr.co.0[pos] as usize;
+    }
+    encode_perm(&perm) * 729 + orient
+}
+
+// Optimal 2x2 solver; with exact distances it only has to walk downhill
+pub struct PocketSolver {}
+
+impl Default for PocketSolver {
+    fn default() -> Self {
+        PocketSolver::new()
+    }
+}
+
+impl PocketSolver {
+    pub fn new() -> Self {
+        lazy_static::initialize(&DISTANCE_TABLE);
+        PocketSolver {}
+    }
+
+    // an optimal solution in U, R and F moves
+    pub fn solve(&self, cube: &PocketCube) -> MoveSequence {
+        let mut res = MoveSequence::new();
+        let mut cur = *cube;
+        let mut dist = cur.distance();
+        while dist > 0 {
+            let (next, r) = POCKET_MOVES.iter()
+                .filter(|&&r| !res.moves().last().is_some_and(|&prev| prune_move(prev, r)))
+                .map(|&r| {
+                    let mut next = cur;
+                    next.rotate(r);
+                    (next, r)
+                })
+                .find(|(next, _)| next.distance() == dist - 1)
+                .unwrap();
+            res.push(r);
+            cur = next;
+            dist -= 1;
+        }
+        res
+    }
+
+    // an optimal solution for the corners of a 3x3 state (edges are ignored),
+    // in the same orientation as `cube`: it may turn any face, not just U, R and F
+    pub fn solve_cube(&self, cube: &CubeSequenceRepr) -> MoveSequence {
+        let (pocket, i) = PocketCube::normalize(cube);
+        let rot = &CUBE_ROTATIONS[i];
+        let inv = rot.inverse();
+        // turning U, R or F of the rotated cube turns some other face of the original
+        let moves = self.solve(&pocket).moves().iter().map(|&m| {
+            let conj = rot.multiply(&CubeSequenceRepr::from(&[m])).multiply(&inv);
+            *ALL_MOVES.iter()
+                .find(|&&r| same_corners(&CubeSequenceRepr::from(&[r]), &conj))
+                .unwrap()
+        }).collect::<Vec<_>>();
+        MoveSequence::from(moves)
+    }
+
+    // a random-state scramble: the inverse of an optimal solution of a random state
+    pub fn scramble<R: Rng + ?Sized>(&self, rng: &mut R) -> MoveSequence {
+        self.solve(&PocketCube::random(rng)).inverse()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_distance_table() {
+        // number of states at each distance in the half turn metric
+        let expected = [1, 9, 54, 321, 1847, 9992, 50136, 227536, 870072, 1887748, 623800, 2644];
+        let mut counts = [0; 12];
+        for i in 0..POCKET_STATES {
+            counts[DISTANCE_TABLE[i] as usize] += 1;
+        }
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn test_solve() {
+        let solver = PocketSolver::new();
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..20 {
+            let cube = PocketCube::random(&mut rng);
+            let solution = solver.solve(&cube);
+            assert_eq!(solution.len(), cube.distance());
+            let mut c = cube;
+            for &m in solution.moves() {
+                c.rotate(m);
+            }
+            assert!(c.is_solved());
+        }
+        let scramble = solver.scramble(&mut rng);
+        assert!(scramble.len() <= 11);
+    }
+
+    #[test]
+    fn test_solve_cube() {
+        // D, L and B turns, and a 3x3 state whose corners need a whole-cube rotation
+        let solver = PocketSolver::new();
+        let moves: MoveSequence = "D L' B2 U R D2 F' L".parse().unwrap();
+        let cube = CubeSequenceRepr::from(moves.moves());
+        let solution = solver.solve_cube(&cube);
+        assert!(solution.len() <= moves.len());
+        let solved = cube.multiply(&CubeSequenceRepr::from(solution.moves()));
+        assert!(CUBE_ROTATIONS.iter().any(|r| same_corners(r, &solved)));
+        assert_eq!(PocketCube::from_cube(&CubeSequenceRepr::from(&[Rotation::D, Rotation::Ur])), PocketCube::new());
+    }
+
+    #[test]
+    fn test_rotate_any_face() {
+        // turning D is turning U, as far as a 2x2 can tell
+        let mut d = PocketCube::new();
+        d.rotate(Rotation::D);
+        let mut u = PocketCube::new();
+        u.rotate(Rotation::U);
+        assert_eq!(d, u);
+        assert_eq!(d.distance(), 1);
+        let solver = PocketSolver::new();
+        let mut rng = StdRng::seed_from_u64(40);
+        for _ in 0..200 {
+            let mut cube = PocketCube::new();
+            for _ in 0..8 {
+                cube.rotate(ALL_MOVES[rng.gen_range(0..18)]);
+            }
+            assert_eq!(PocketCube::from_cube(&CubeSequenceRepr { cp: cube.cp, co: cube.co, ..CubeSequenceRepr::new() }), cube);
+            let solution = solver.solve(&cube);
+            assert!(solution.len() <= 8);
+            for &m in solution.moves() {
+                cube.rotate(m);
+            }
+            assert!(cube.is_solved());
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::Write;
+use std::io::IsTerminal;
+
+use crate::color::Color;
+use crate::rubiks_cube::ColoredCube;
+
+// ANSI terminal rendering of ColoredCube
+
+// channel levels of the 6x6x6 colour cube in the xterm 256-colour palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorMode {
+    // 24-bit colour escapes
+    TrueColor,
+    // xterm 256-colour palette escapes
+    Ansi256,
+    // no escapes at all, same letters as ColoredCube's Display
+    Plain,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct AnsiRenderer {
+    pub mode: ColorMode,
+    // two sticker rows per line using half blocks, one column per sticker
+    pub compact: bool,
+}
+
+impl AnsiRenderer {
+    pub fn new(mode: ColorMode, compact: bool) -> Self {
+        AnsiRenderer { mode, compact }
+    }
+
+    // plain output unless stdout is a terminal (and NO_COLOR isn't set),
+    // truecolor if the terminal says it supports it, 256 colours otherwise
+    pub fn detect() -> Self {
+        let mode = if !std::io::stdout().is_terminal() || std::env::var_os("NO_COLOR").is_some() {
+            ColorMode::Plain
+        } else {
+            match std::env::var("COLORTERM") {
+                Ok(v) if v == "truecolor" || v == "24bit" => ColorMode::TrueColor,
+                _ => ColorMode::Ansi256,
+            }
+        };
+        AnsiRenderer::new(mode, false)
+    }
+
+    fn escape(&self, color: Color, background: bool) -> String {
+        let layer = if background { 48 } else { 38 };
+        let (r, g, b) = color.rgb;
+        match self.mode {
+            ColorMode::TrueColor => format!("\x1b[{};2;{};{};{}m", layer, r, g, b),
+            ColorMode::Ansi256 => format!("\x1b[{};5;{}m", layer, palette_index(color)),
+            ColorMode::Plain => String::new(),
+        }
+    }
+
+    pub fn render<const N: usize>(&self, cube: &ColoredCube<N>) -> String {
+        if self.mode == ColorMode::Plain {
+            return cube.to_string();
+        }
+        let net = cube.net();
+        let mut res = String::new();
+        if self.compact {
+            for rows in net.chunks(2) {
+                for col in 0..4 * N {
+                    let top = rows[0][col];
+                    let bottom = rows.get(1).and_then(|r| r[col]);
+                    match (top, bottom) {
+                        (Some(t), Some(b)) => write!(res, "{}{}\u{2580}{}", self.escape(cube.scheme.color(t), false), self.escape(cube.scheme.color(b), true), RESET).unwrap(),
+                        (Some(t), None) => write!(res, "{}\u{2580}{}", self.escape(cube.scheme.color(t), false), RESET).unwrap(),
+                        (None, Some(b)) => write!(res, "{}\u{2584}{}", self.escape(cube.scheme.color(b), false), RESET).unwrap(),
+                        (None, None) => res.push(' '),
+                    }
+                }
+                res.push('\n');
+            }
+        } else {
+            for row in net.iter() {
+                for sticker in row.iter() {
+                    match sticker {
+                        Some(f) => write!(res, "{}  {}", self.escape(cube.scheme.color(*f), true), RESET).unwrap(),
+                        None => res.push_str("  "),
+                    }
+                }
+                res.push('\n');
+            }
+        }
+        res
+    }
+}
+
+// nearest entry of the palette's colour cube, channel by channel
+fn palette_index(color: Color) -> u8 {
+    let level = |v: u8| (0..6).min_by_key(|&i| (i16::from(CUBE_LEVELS[i]) - i16::from(v)).abs()).unwrap() as u8;
+    let (r, g, b) = color.rgb;
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rubiks_cube::{CubeSequenceRepr, Rotation};
+
+    fn cube() -> ColoredCube {
+        let mut vis = ColoredCube::new();
+        CubeSequenceRepr::from(&[Rotation::R, Rotation::U]).visualize(&mut vis);
+        vis
+    }
+
+    #[test]
+    fn test_plain() {
+        let vis = cube();
+        assert_eq!(AnsiRenderer::new(ColorMode::Plain, false).render(&vis), vis.to_string());
+        assert_eq!(AnsiRenderer::new(ColorMode::Plain, true).render(&vis), vis.to_string());
+    }
+
+    #[test]
+    fn test_colored() {
+        let vis = cube();
+        let blocks = AnsiRenderer::new(ColorMode::Ansi256, false).render(&vis);
+        assert_eq!(blocks.lines().count(), 9);
+        assert_eq!(blocks.matches("\x1b[48;5;").count(), 54);
+        let compact = AnsiRenderer::new(ColorMode::TrueColor, true).render(&vis);
+        assert_eq!(compact.lines().count(), 5);
+        assert!(compact.contains("\x1b[38;2;255;255;255m"));
+    }
+}
@@ -0,0 +1,21 @@
+// Rendering cube states for people: coloured terminal output, and SVG or PNG images
+
+use crate::color::Color;
+
+pub mod ansi;
+pub mod svg;
+#[cfg(feature = "png")]
+pub mod png;
+
+// An image view as plain shapes, margin not included, for the image renderers to
+// turn into their format; later shapes go on top
+pub(crate) struct Drawing {
+    pub width: f64,
+    pub height: f64,
+    pub shapes: Vec<Shape>,
+}
+
+pub(crate) enum Shape {
+    Rect { color: Color, x: f64, y: f64, w: f64, h: f64 },
+    Polygon { color: Color, points: Vec<(f64, f64)> },
+}
@@ -0,0 +1,204 @@
+use std::fmt::{Display, Formatter};
+
+use crate::color::Color;
+use crate::render::{Drawing, Shape};
+use crate::render::svg::{SvgRenderer, SvgView};
+use crate::rubiks_cube::ColoredCube;
+
+// PNG images of ColoredCube, in the same views as SvgRenderer: the shapes are
+// laid out the same way and filled in on a transparent background, with a black
+// outline about a pixel wide
+
+const STROKE: [u8; 4] = [0, 0, 0, 255];
+
+// longest side of an image, so a stray sticker size can't ask for gigabytes
+const MAX_SIDE: f64 = 16384.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PngError {
+    // the image for this sticker size and margin would be under 1px or over
+    // MAX_SIDE on a side, or not a size at all (NaN)
+    BadSize { width: f64, height: f64 },
+    // what the PNG encoder had to say
+    Encoding(String),
+}
+
+impl Display for PngError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PngError::BadSize { width, height } => write!(f, "can't make a {} by {} pixel image", width, height),
+            PngError::Encoding(e) => write!(f, "PNG encoding failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PngError {}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PngRenderer {
+    // edge length of one sticker in pixels
+    pub sticker: f64,
+    // empty space around the drawing, in pixels
+    pub margin: f64,
+}
+
+impl Default for PngRenderer {
+    fn default() -> Self {
+        PngRenderer::new()
+    }
+}
+
+impl PngRenderer {
+    pub fn new() -> Self {
+        PngRenderer { sticker: 30.0, margin: 2.0 }
+    }
+
+    // the encoded PNG file
+    pub fn render<const N: usize>(&self, cube: &ColoredCube<N>, view: SvgView) -> Result<Vec<u8>, PngError> {
+        let layout = SvgRenderer { sticker: self.sticker, margin: self.margin };
+        let drawing = layout.draw(cube, view);
+        let width = drawing.width + 2.0 * self.margin;
+        let height = drawing.height + 2.0 * self.margin;
+        if !((1.0..=MAX_SIDE).contains(&width) && (1.0..=MAX_SIDE).contains(&height)) {
+            return Err(PngError::BadSize { width, height });
+        }
+        let (width, height, pixels) = self.rasterize(&drawing);
+        let mut out = vec![];
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let encoding = |e: png::EncodingError| PngError::Encoding(e.to_string());
+        let mut writer = encoder.write_header().map_err(encoding)?;
+        writer.write_image_data(&pixels).map_err(encoding)?;
+        writer.finish().map_err(encoding)?;
+        Ok(out)
+    }
+
+    // RGBA rows, top to bottom
+    fn rasterize(&self, drawing: &Drawing) -> (u32, u32, Vec<u8>) {
+        let m = self.margin;
+        let width = (drawing.width + 2.0 * m).ceil() as usize;
+        let height = (drawing.height + 2.0 * m).ceil() as usize;
+        let mut pixels = vec![0; width * height * 4];
+        for shape in drawing.shapes.iter() {
+            let (color, points) = match shape {
+                Shape::Rect { color, x, y, w, h } => (*color, vec![(*x, *y), (x + w, *y), (x + w, y + h), (*x, y + h)]),
+                Shape::Polygon { color, points } => (*color, points.clone()),
+            };
+            let points: Vec<(f64, f64)> = points.iter().map(|(x, y)| (x + m, y + m)).collect();
+            fill_polygon(&mut pixels, width, height, color, &points);
+        }
+        (width as u32, height as u32, pixels)
+    }
+}
+
+// every view is made of convex shapes, so a pixel is inside when it is on the same
+// side of all the edges; the outline is 1px wide centred on the edges, like SVG's
+// default stroke
+fn fill_polygon(pixels: &mut [u8], width: usize, height: usize, color: Color, points: &[(f64, f64)]) {
+    let (r, g, b) = color.rgb;
+    let edges: Vec<((f64, f64), (f64, f64))> = points.iter().zip(points.iter().cycle().skip(1))
+        .map(|(p, q)| (*p, *q))
+        .collect();
+    let (mut x0, mut y0, mut x1, mut y1) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    for (x, y) in points.iter() {
+        x0 = x0.min(*x);
+        y0 = y0.min(*y);
+        x1 = x1.max(*x);
+        y1 = y1.max(*y);
+    }
+    let columns = (x0 - 1.0).floor().max(0.0) as usize..((x1 + 1.0).ceil() as usize).min(width);
+    let rows = (y0 - 1.0).floor().max(0.0) as usize..((y1 + 1.0).ceil() as usize).min(height);
+    for py in rows {
+        for px in columns.clone() {
+            let p = (px as f64 + 0.5, py as f64 + 0.5);
+            let sides: Vec<f64> = edges.iter().map(|(a, b)| cross(*a, *b, p)).collect();
+            let inside = sides.iter().all(|s| *s >= 0.0) || sides.iter().all(|s| *s <= 0.0);
+            let near_edge = edges.iter().any(|(a, b)| distance(*a, *b, p) <= 0.5);
+            let i = (py * width + px) * 4;
+            if near_edge {
+                pixels[i..i + 4].copy_from_slice(&STROKE);
+            } else if inside {
+                pixels[i..i + 4].copy_from_slice(&[r, g, b, 255]);
+            }
+        }
+    }
+}
+
+// which side of the line through a and b the point p is on
+fn cross(a: (f64, f64), b: (f64, f64), p: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+// from p to the segment from a to b
+fn distance(a: (f64, f64), b: (f64, f64), p: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let t = (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / (dx * dx + dy * dy)).clamp(0.0, 1.0);
+    let (x, y) = (a.0 + t * dx - p.0, a.1 + t * dy - p.1);
+    (x * x + y * y).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rubiks_cube::{CubeSequenceRepr, Face, Rotation};
+
+    fn decode(data: &[u8]) -> (u32, u32, Vec<u8>) {
+        let mut reader = png::Decoder::new(data).read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!(info.color_type, png::ColorType::Rgba);
+        buf.truncate(info.buffer_size());
+        (info.width, info.height, buf)
+    }
+
+    #[test]
+    fn test_net() {
+        let mut vis = ColoredCube::new();
+        CubeSequenceRepr::from(&[Rotation::R]).visualize(&mut vis);
+        let (w, h, pixels) = decode(&PngRenderer::new().render(&vis, SvgView::Net).unwrap());
+        // 12 by 9 stickers of 30px, and 2px all round
+        assert_eq!((w, h), (364, 274));
+        let at = |x: usize, y: usize| {
+            let i = (y * w as usize + x) * 4;
+            pixels[i..i + 4].to_vec()
+        };
+        let rgba = |f| {
+            let (r, g, b) = vis.scheme.color(f).rgb;
+            vec![r, g, b, 255]
+        };
+        // the middle of each sticker, then its outline and the space outside the net
+        let sticker = |row: usize, col: usize| at(2 + col * 30 + 15, 2 + row * 30 + 15);
+        assert_eq!(sticker(0, 4), rgba(Face::U));
+        assert_eq!(sticker(0, 5), rgba(Face::F));
+        assert_eq!(sticker(4, 4), rgba(Face::F));
+        assert_eq!(sticker(4, 7), rgba(Face::R));
+        assert_eq!(at(2, 2 + 3 * 30 + 15), STROKE.to_vec());
+        assert_eq!(at(10, 10), vec![0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_views() {
+        let mut vis = ColoredCube::new();
+        CubeSequenceRepr::new().visualize(&mut vis);
+        let r = PngRenderer { sticker: 10.0, margin: 0.0 };
+        let (w, h, pixels) = decode(&r.render(&vis, SvgView::Isometric).unwrap());
+        assert_eq!((w, h), (52, 60));
+        // the middle of U, and of F and R below it on either side
+        let at = |x: usize, y: usize, f| {
+            let (r, g, b) = vis.scheme.color(f).rgb;
+            let i = (y * w as usize + x) * 4;
+            assert_eq!(pixels[i..i + 4], [r, g, b, 255]);
+        };
+        at(26, 15, Face::U);
+        at(13, 37, Face::F);
+        at(39, 37, Face::R);
+        let (w, h, _) = decode(&r.render(&vis, SvgView::LastLayer).unwrap());
+        assert_eq!((w, h), (39, 39));
+        // nothing to draw, or far too much
+        let empty = PngRenderer { sticker: 0.0, margin: 0.0 };
+        assert_eq!(empty.render(&vis, SvgView::Net), Err(PngError::BadSize { width: 0.0, height: 0.0 }));
+        assert!(PngRenderer { sticker: f64::NAN, margin: 0.0 }.render(&vis, SvgView::Net).is_err());
+        assert!(PngRenderer { sticker: 1e6, margin: 0.0 }.render(&vis, SvgView::Net).is_err());
+    }
+}
@@ -0,0 +1,203 @@
+use std::fmt::Write;
+
+use crate::color::Color;
+use crate::render::{Drawing, Shape};
+use crate::rubiks_cube::{ColoredCube, Face};
+
+// SVG images of ColoredCube: the flat net, an isometric view of U, F and R,
+// and the top-down last layer view used on algorithm sheets
+
+const STROKE: &str = "#000";
+
+// cos 30°, for the isometric projection
+const ISO_X: f64 = 0.866_025_403_784_438_6;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SvgView {
+    Net,
+    Isometric,
+    LastLayer,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SvgRenderer {
+    // edge length of one sticker in user units
+    pub sticker: f64,
+    // empty space around the drawing
+    pub margin: f64,
+}
+
+impl Default for SvgRenderer {
+    fn default() -> Self {
+        SvgRenderer::new()
+    }
+}
+
+impl SvgRenderer {
+    pub fn new() -> Self {
+        SvgRenderer { sticker: 30.0, margin: 2.0 }
+    }
+
+    pub fn render<const N: usize>(&self, cube: &ColoredCube<N>, view: SvgView) -> String {
+        self.document(&self.draw(cube, view))
+    }
+
+    // same cross layout as ColoredCube's Display
+    pub fn net<const N: usize>(&self, cube: &ColoredCube<N>) -> String {
+        self.render(cube, SvgView::Net)
+    }
+
+    // U, F and R seen from the front-right-top corner
+    pub fn isometric<const N: usize>(&self, cube: &ColoredCube<N>) -> String {
+        self.render(cube, SvgView::Isometric)
+    }
+
+    // U from above with B at the top, and the top row of each side face as a strip around it
+    pub fn last_layer<const N: usize>(&self, cube: &ColoredCube<N>) -> String {
+        self.render(cube, SvgView::LastLayer)
+    }
+
+    // the shapes of a view, sized by `sticker`; the PNG renderer draws the same ones
+    pub(crate) fn draw<const N: usize>(&self, cube: &ColoredCube<N>, view: SvgView) -> Drawing {
+        match view {
+            SvgView::Net => self.draw_net(cube),
+            SvgView::Isometric => self.draw_isometric(cube),
+            SvgView::LastLayer => self.draw_last_layer(cube),
+        }
+    }
+
+    fn draw_net<const N: usize>(&self, cube: &ColoredCube<N>) -> Drawing {
+        let s = self.sticker;
+        let mut shapes = vec![];
+        for (i, row) in cube.net().iter().enumerate() {
+            for (j, sticker) in row.iter().enumerate() {
+                if let Some(face) = sticker {
+                    shapes.push(Shape::Rect { color: cube.scheme.color(*face), x: j as f64 * s, y: i as f64 * s, w: s, h: s });
+                }
+            }
+        }
+        let n = N as f64;
+        Drawing { width: 4.0 * n * s, height: 3.0 * n * s, shapes }
+    }
+
+    fn draw_isometric<const N: usize>(&self, cube: &ColoredCube<N>) -> Drawing {
+        let (s, n) = (self.sticker, N as f64);
+        // x goes right, y up, d away from the viewer; the cube spans [0, N] on each
+        let project = |x: f64, y: f64, d: f64| ((x + d) * ISO_X * s, (x - d) * 0.5 * s - y * s + 1.5 * n * s);
+        let mut shapes = vec![];
+        for i in 0..N {
+            for j in 0..N {
+                let (i, jf) = (i as f64, j as f64);
+                // U: row 0 at the back, column 0 on the left
+                let d = n - 1.0 - i;
+                shapes.push(Shape::Polygon { color: cube.scheme.color(cube[Face::U][i as usize][j]), points: vec![
+                    project(jf, n, d), project(jf + 1.0, n, d),
+                    project(jf + 1.0, n, d + 1.0), project(jf, n, d + 1.0),
+                ] });
+                // F: row 0 on top, column 0 on the left
+                let y = n - 1.0 - i;
+                shapes.push(Shape::Polygon { color: cube.scheme.color(cube[Face::F][i as usize][j]), points: vec![
+                    project(jf, y + 1.0, 0.0), project(jf + 1.0, y + 1.0, 0.0),
+                    project(jf + 1.0, y, 0.0), project(jf, y, 0.0),
+                ] });
+                // R: row 0 on top, column 0 next to F
+                shapes.push(Shape::Polygon { color: cube.scheme.color(cube[Face::R][i as usize][j]), points: vec![
+                    project(n, y + 1.0, jf), project(n, y + 1.0, jf + 1.0),
+                    project(n, y, jf + 1.0), project(n, y, jf),
+                ] });
+            }
+        }
+        Drawing { width: 2.0 * n * ISO_X * s, height: 2.0 * n * s, shapes }
+    }
+
+    fn draw_last_layer<const N: usize>(&self, cube: &ColoredCube<N>) -> Drawing {
+        let s = self.sticker;
+        let strip = s / 3.0;
+        let gap = s / 10.0;
+        let start = strip + gap;
+        let mut shapes = vec![];
+        for i in 0..N {
+            for j in 0..N {
+                shapes.push(Shape::Rect { color: cube.scheme.color(cube[Face::U][i][j]), x: start + j as f64 * s, y: start + i as f64 * s, w: s, h: s });
+            }
+        }
+        let far = start + N as f64 * s + gap;
+        for k in 0..N {
+            let along = start + k as f64 * s;
+            // column k of U lines up with F's column k and B's column N - 1 - k;
+            // row k of U with L's column k and R's column N - 1 - k
+            shapes.push(Shape::Rect { color: cube.scheme.color(cube[Face::F][0][k]), x: along, y: far, w: s, h: strip });
+            shapes.push(Shape::Rect { color: cube.scheme.color(cube[Face::B][0][N - 1 - k]), x: along, y: 0.0, w: s, h: strip });
+            shapes.push(Shape::Rect { color: cube.scheme.color(cube[Face::L][0][k]), x: 0.0, y: along, w: strip, h: s });
+            shapes.push(Shape::Rect { color: cube.scheme.color(cube[Face::R][0][N - 1 - k]), x: far, y: along, w: strip, h: s });
+        }
+        Drawing { width: far + strip, height: far + strip, shapes }
+    }
+
+    fn fill(color: Color) -> String {
+        let (r, g, b) = color.rgb;
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
+    fn document(&self, drawing: &Drawing) -> String {
+        let m = self.margin;
+        let mut body = String::new();
+        for shape in drawing.shapes.iter() {
+            match shape {
+                Shape::Rect { color, x, y, w, h } => {
+                    writeln!(body, "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\" stroke=\"{}\"/>",
+                             x + m, y + m, w, h, SvgRenderer::fill(*color), STROKE).unwrap();
+                }
+                Shape::Polygon { color, points } => {
+                    let points: Vec<String> = points.iter()
+                        .map(|(x, y)| format!("{:.2},{:.2}", x + m, y + m))
+                        .collect();
+                    writeln!(body, "<polygon points=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-linejoin=\"round\"/>",
+                             points.join(" "), SvgRenderer::fill(*color), STROKE).unwrap();
+                }
+            }
+        }
+        let (w, h) = (drawing.width + 2.0 * m, drawing.height + 2.0 * m);
+        format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.2}\" height=\"{:.2}\" viewBox=\"0 0 {:.2} {:.2}\">\n{}</svg>\n",
+                w, h, w, h, body)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::big_cube::BigCube;
+    use crate::rubiks_cube::{CubeSequenceRepr, Rotation};
+
+    #[test]
+    fn test_views() {
+        let mut vis = ColoredCube::new();
+        CubeSequenceRepr::from(&[Rotation::R, Rotation::U]).visualize(&mut vis);
+        let r = SvgRenderer::new();
+        let net = r.net(&vis);
+        assert!(net.starts_with("<svg") && net.ends_with("</svg>\n"));
+        assert_eq!(net.matches("<rect").count(), 54);
+        assert_eq!(r.isometric(&vis).matches("<polygon").count(), 27);
+        assert_eq!(r.last_layer(&vis).matches("<rect").count(), 21);
+        let mut big = ColoredCube::<4>::new();
+        BigCube::<4>::new().visualize(&mut big);
+        assert_eq!(r.net(&big).matches("<rect").count(), 96);
+        assert_eq!(r.isometric(&big).matches("<polygon").count(), 48);
+        assert_eq!(r.last_layer(&big).matches("<rect").count(), 32);
+    }
+
+    #[test]
+    fn test_last_layer_sides() {
+        // after U the front strip shows what was on R, the left strip what was on F
+        let mut vis = ColoredCube::new();
+        CubeSequenceRepr::from(&[Rotation::U]).visualize(&mut vis);
+        let ll = SvgRenderer::new().last_layer(&vis);
+        let fills: Vec<&str> = ll.lines().filter_map(|l| l.split("fill=\"").nth(1)).map(|f| &f[..7]).collect();
+        let hex = |f| SvgRenderer::fill(vis.scheme.color(f));
+        // U stickers come first, then F, B, L, R for each k
+        assert_eq!(fills[9], hex(Face::R));
+        assert_eq!(fills[10], hex(Face::L));
+        assert_eq!(fills[11], hex(Face::F));
+        assert_eq!(fills[12], hex(Face::B));
+    }
+}
@@ -0,0 +1,645 @@
+use crate::big_cube::BigMove;
+use crate::goal::{PieceGroup, PieceSet};
+use crate::heuristic::{prune_move, PruneTable};
+use crate::num_traits::{FromPrimitive, ToPrimitive};
+use crate::rubiks_cube::{co_encode, corner_stickers, cp_encode, edge_stickers, CornerOrient, CornerPerm, CubeRepr, CubeSequenceRepr, Face, ParseError, Rotation};
+use crate::sequence::MoveSequence;
+use crate::solver::Budget;
+use crate::util::encode_perm;
+
+// Restricted-generator solving, e.g. <R, U> or <R, U, F>
+//
+// RestrictedSolver takes face turns only; SliceSolver takes turns of any layers
+// (<M, U>, <R, r, U>, ...), which move the centres, so it counts a cube as
+// solved in any of the 24 orientations.
+
+// 24 corner stickers, 24 edge stickers, then the centres in Face order
+pub(crate) const POINTS: usize = 54;
+
+pub(crate) type Perm = [u8; POINTS];
+
+pub(crate) const IDENTITY: Perm = {
+    let mut p = [0u8; POINTS];
+    let mut i = 0;
+    while i < POINTS {
+        p[i] = i as u8;
+        i += 1;
+    }
+    p
+};
+
+// apply `a`, then `b`
+pub(crate) fn mul(a: &Perm, b: &Perm) -> Perm {
+    let mut res = [0u8; POINTS];
+    for i in 0..POINTS {
+        res[i] = b[a[i] as usize];
+    }
+    res
+}
+
+pub(crate) fn inv(a: &Perm) -> Perm {
+    let mut res = [0u8; POINTS];
+    for i in 0..POINTS {
+        res[a[i] as usize] = i as u8;
+    }
+    res
+}
+
+// where each sticker of the solved cube ends up
+pub(crate) fn to_perm(cube: &CubeSequenceRepr) -> Perm {
+    let mut res = [0u8; POINTS];
+    for i in 0..8 {
+        let (c, t) = (cube.cp.0[i] as usize, cube.co.0[i] as usize);
+        for o in 0..3 {
+            res[c * 3 + o] = (i * 3 + (o + t) % 3) as u8;
+        }
+    }
+    for i in 0..12 {
+        let (e, f) = (cube.ep.0[i] as usize, cube.eo.0[i] as usize);
+        for o in 0..2 {
+            res[24 + e * 2 + o] = (24 + i * 2 + (o ^ f)) as u8;
+        }
+    }
+    for (i, p) in res.iter_mut().enumerate().skip(48) {
+        *p = i as u8;
+    }
+    res
+}
+
+// and back, for a perm that moves whole pieces and leaves the centres alone
+pub(crate) fn from_perm(p: &Perm) -> CubeSequenceRepr {
+    let mut res = CubeSequenceRepr::new();
+    for c in 0..8 {
+        let to = p[c * 3] as usize;
+        res.cp.0[to / 3] = c as u8;
+        res.co.0[to / 3] = (to % 3) as u8;
+    }
+    for e in 0..12 {
+        let to = p[24 + e * 2] as usize - 24;
+        res.ep.0[to / 2] = e as u8;
+        res.eo.0[to / 2] = to % 2 == 1;
+    }
+    res
+}
+
+lazy_static! {
+    // (face, row, column) of each point
+    pub(crate) static ref FACELETS: [(Face, usize, usize); POINTS] = {
+        let mut res = [(Face::U, 1, 1); POINTS];
+        for i in 0..8 {
+            // to_perm counts twist the other way round the corner
+            let [a, b, c] = corner_stickers(i);
+            res[i * 3..i * 3 + 3].copy_from_slice(&[a, c, b]);
+        }
+        for i in 0..12 {
+            res[24 + i * 2..24 + i * 2 + 2].copy_from_slice(&edge_stickers(i));
+        }
+        for (f, p) in res[48..].iter_mut().enumerate() {
+            p.0 = Face::from_usize(f).unwrap();
+        }
+        res
+    };
+
+    // the whole-cube rotations, generated by x (3Rw) and y (3Uw)
+    static ref ROTATIONS: Vec<Perm> = {
+        let gens = [Face::R, Face::U].map(|face| turn_perm(&BigMove { face, depth: 3, wide: true, turns: 1 }));
+        let mut res = vec![IDENTITY];
+        let mut i = 0;
+        while i < res.len() {
+            for g in gens.iter() {
+                let p = mul(&res[i], g);
+                if !res.contains(&p) {
+                    res.push(p);
+                }
+            }
+            i += 1;
+        }
+        res
+    };
+}
+
+// where a turn of a 3x3 sends each point
+fn turn_perm(m: &BigMove) -> Perm {
+    let mut res = [0u8; POINTS];
+    for (i, &(f, r, c)) in FACELETS.iter().enumerate() {
+        let to = m.destination(f, r, c, 3);
+        res[i] = FACELETS.iter().position(|&x| x == to).unwrap() as u8;
+    }
+    res
+}
+
+// The subgroup generated by a set of moves, as a Schreier-Sims stabiliser chain
+// (Knuth's variant); level k holds elements fixing every point above k
+pub struct Subgroup {
+    generators: Vec<Vec<Perm>>,
+    transversal: Vec<Vec<Option<Perm>>>,
+}
+
+impl Subgroup {
+    pub fn new(moves: &[Rotation]) -> Self {
+        let perms: Vec<Perm> = moves.iter().map(|r| to_perm(&CubeSequenceRepr::from(&[*r]))).collect();
+        Subgroup::generated_by(&perms)
+    }
+
+    // the subgroup generated by turns of any layers, e.g. <M, U>
+    pub fn with_turns(turns: &[BigMove]) -> Self {
+        let perms: Vec<Perm> = turns.iter().map(turn_perm).collect();
+        Subgroup::generated_by(&perms)
+    }
+
+    fn generated_by(perms: &[Perm]) -> Self {
+        let mut res = Subgroup {
+            generators: vec![vec![]; POINTS],
+            transversal: vec![vec![None; POINTS]; POINTS],
+        };
+        for k in 0..POINTS {
+            res.transversal[k][k] = Some(IDENTITY);
+        }
+        for p in perms {
+            if !res.contains_perm(p) {
+                res.add(POINTS - 1, *p);
+            }
+        }
+        res
+    }
+
+    fn add(&mut self, k: usize, p: Perm) {
+        self.generators[k].push(p);
+        let reps: Vec<Perm> = self.transversal[k].iter().flatten().cloned().collect();
+        for s in reps.iter() {
+            self.extend(k, mul(s, &p));
+        }
+    }
+
+    fn extend(&mut self, k: usize, p: Perm) {
+        let j = p[k] as usize;
+        match self.transversal[k][j] {
+            Some(t) => {
+                let rest = mul(&p, &inv(&t));
+                if rest != IDENTITY && !self.contains_perm_below(k, &rest) {
+                    self.add(k - 1, rest);
+                }
+            }
+            None => {
+                self.transversal[k][j] = Some(p);
+                let gens = self.generators[k].clone();
+                for g in gens.iter() {
+                    self.extend(k, mul(&p, g));
+                }
+            }
+        }
+    }
+
+    fn sift(&self, top: usize, p: &Perm) -> Option<Perm> {
+        let mut p = *p;
+        for k in (0..=top).rev() {
+            let j = p[k] as usize;
+            if j != k {
+                p = mul(&p, &inv(self.transversal[k][j].as_ref()?));
+            }
+        }
+        Some(p)
+    }
+
+    fn contains_perm_below(&self, k: usize, p: &Perm) -> bool {
+        k > 0 && self.sift(k - 1, p) == Some(IDENTITY)
+    }
+
+    fn contains_perm(&self, p: &Perm) -> bool {
+        self.sift(POINTS - 1, p) == Some(IDENTITY)
+    }
+
+    // whether the state can be solved using only the generators, in any
+    // orientation: the cube keeps its centres home, but slice turns don't
+    pub fn contains(&self, cube: &CubeSequenceRepr) -> bool {
+        let back = inv(&to_perm(cube));
+        ROTATIONS.iter().any(|r| self.contains_perm(&mul(&back, r)))
+    }
+
+    // number of states in the subgroup
+    pub fn order(&self) -> u128 {
+        self.transversal.iter().map(|t| t.iter().flatten().count() as u128).product()
+    }
+}
+
+// IDA* solver using a user-defined move set, optimal within that set
+//
+// The tables only follow corners and groups of four edges, which is plenty for
+// small subgroups like <R, U>; given all 18 moves it's an optimal solver, but
+// only for short scrambles (10 or so moves), random states take far too long
+// (OptimalSolver in optimal.rs is the one for those). Every power of each move
+// given is allowed too, so <R, U> is the same as <R, R', R2, U, U', U2>.
+pub struct RestrictedSolver {
+    moves: Vec<Rotation>,
+    subgroup: Subgroup,
+    cp_table: PruneTable<CornerPerm>,
+    co_table: PruneTable<CornerOrient>,
+    edge_groups: Vec<PieceGroup>,
+    edge_tables: Vec<PruneTable<PieceSet>>,
+}
+
+impl RestrictedSolver {
+    pub fn new(moves: &[Rotation]) -> Self {
+        // the search never turns a face twice in a row, so it needs all its powers
+        let mut all = vec![];
+        for r in moves.iter() {
+            for turns in 1..4 {
+                let p = Rotation::from_quarter_turns(*r, turns).unwrap();
+                if !all.contains(&p) {
+                    all.push(p);
+                }
+            }
+        }
+        let moves = all.as_slice();
+        let mut cp_table = PruneTable::new(40320);
+        cp_table.init(cp_encode, &[CornerPerm::new()], moves);
+        let mut co_table = PruneTable::new(2187);
+        co_table.init(co_encode, &[CornerOrient::new()], moves);
+        let edge_groups: Vec<PieceGroup> = (0..3).map(|i| PieceGroup {
+            edges: (i * 4..i * 4 + 4).collect(),
+            corners: vec![],
+        }).collect();
+        let edge_tables = edge_groups.iter().map(|g| {
+            let mut pt = PruneTable::new(PieceSet::table_size(g));
+            pt.init(PieceSet::encode, &[PieceSet::solved(g)], moves);
+            pt
+        }).collect();
+        RestrictedSolver {
+            moves: moves.to_vec(),
+            subgroup: Subgroup::new(moves),
+            cp_table,
+            co_table,
+            edge_groups,
+            edge_tables,
+        }
+    }
+
+    pub fn subgroup(&self) -> &Subgroup {
+        &self.subgroup
+    }
+
+    // shortest solution using only the allowed moves; None if the state isn't in
+    // the subgroup or needs more than `max_depth` moves
+    pub fn solve(&self, cube: &CubeSequenceRepr, max_depth: usize) -> Option<MoveSequence> {
+        if !self.subgroup.contains(cube) {
+            return None;
+        }
+        self.solve_within(cube, max_depth, &mut Budget::new(None))
+    }
+
+    // the search alone, for a state known to be in the subgroup, giving up once
+    // `budget` runs out
+    pub(crate) fn solve_within(&self, cube: &CubeSequenceRepr, max_depth: usize, budget: &mut Budget) -> Option<MoveSequence> {
+        let edges: Vec<PieceSet> = self.edge_groups.iter().map(|g| PieceSet::from_cube(g, cube)).collect::<Option<_>>()?;
+        let mut moves = vec![];
+        let max_depth = max_depth.min(i8::MAX as usize) as i8;
+        for depth in self.h(cube, &edges)..=max_depth {
+            if self.search(cube, &edges, depth, &mut moves, budget) {
+                return Some(MoveSequence::from(moves));
+            }
+            if budget.expired {
+                break;
+            }
+        }
+        None
+    }
+
+    fn h(&self, cube: &CubeSequenceRepr, edges: &[PieceSet]) -> i8 {
+        let mut res = self.cp_table[cp_encode(&cube.cp)].max(self.co_table[co_encode(&cube.co)]);
+        for (pt, s) in self.edge_tables.iter().zip(edges.iter()) {
+            res = res.max(pt[s.encode()]);
+        }
+        res
+    }
+
+    fn search(&self, cube: &CubeSequenceRepr, edges: &[PieceSet], depth: i8, moves: &mut Vec<Rotation>, budget: &mut Budget) -> bool {
+        if budget.tick() {
+            return false;
+        }
+        if depth == 0 {
+            return *cube == CubeSequenceRepr::new();
+        }
+        for r in self.moves.iter() {
+            if !moves.is_empty() && prune_move(*moves.last().unwrap(), *r) {
+                continue;
+            }
+            let mut nc = *cube;
+            nc.rotate(*r);
+            let mut ne = edges.to_vec();
+            for e in ne.iter_mut() {
+                e.rotate(*r);
+            }
+            if self.h(&nc, &ne) < depth {
+                moves.push(*r);
+                if self.search(&nc, &ne, depth - 1, moves, budget) {
+                    return true;
+                }
+                moves.pop();
+            }
+        }
+        false
+    }
+}
+
+// Turns of a 3x3 in the usual notation: face turns, wide turns (`Rw`, `r`) and
+// the slices M (as L), E (as D) and S (as F); `x`, `y` and `z` are left out,
+// since they'd never be worth a move
+pub fn parse_turns(s: &str) -> Result<Vec<BigMove>, ParseError> {
+    s.split_whitespace().map(|tok| {
+        let invalid = || ParseError::InvalidMove(tok.to_string());
+        let mut chars = tok.chars();
+        let first = chars.next().ok_or_else(invalid)?;
+        let (face, depth, wide) = match first {
+            'M' => ('L', 2, false),
+            'E' => ('D', 2, false),
+            'S' => ('F', 2, false),
+            'u' | 'd' | 'r' | 'l' | 'f' | 'b' => (first.to_ascii_uppercase(), 2, true),
+            _ => {
+                let m: BigMove = tok.parse()?;
+                return if m.depth > 3 { Err(invalid()) } else { Ok(m) };
+            }
+        };
+        let suffix = chars.as_str();
+        if suffix.starts_with('w') {
+            return Err(invalid());
+        }
+        let m: BigMove = format!("{}{}", face, suffix).parse().map_err(|_| invalid())?;
+        Ok(BigMove { depth, wide, ..m })
+    }).collect()
+}
+
+// the name of a turn of a 3x3, as parse_turns reads it
+pub fn turn_name(m: &BigMove) -> String {
+    let slice = match m.face {
+        Face::L | Face::R => 'M',
+        Face::D | Face::U => 'E',
+        Face::F | Face::B => 'S',
+    };
+    if m.depth != 2 || m.wide {
+        return m.to_string();
+    }
+    // slices turn the way of L, D and F
+    let turns = match m.face {
+        Face::L | Face::D | Face::F => m.turns,
+        _ => 4 - m.turns,
+    };
+    let suffix = match turns {
+        2 => "2",
+        3 => "'",
+        _ => "",
+    };
+    format!("{}{}", slice, suffix)
+}
+
+// The pieces a heuristic table follows; each only depends on where its own
+// stickers are, so any state that agrees on them is as far from solved
+#[derive(Debug, Copy, Clone)]
+enum Projection {
+    CornerPerm,
+    CornerTwist,
+    // four edges, from 4 * .0
+    Edges(usize),
+}
+
+impl Projection {
+    fn size(self) -> usize {
+        match self {
+            Projection::CornerPerm => 40320,
+            Projection::CornerTwist => 2187,
+            Projection::Edges(_) => 24 * 24 * 24 * 24,
+        }
+    }
+
+    fn encode(self, p: &Perm) -> usize {
+        match self {
+            Projection::CornerPerm => {
+                let mut perm = [0u8; 8];
+                for (c, x) in perm.iter_mut().enumerate() {
+                    *x = p[c * 3] / 3;
+                }
+                encode_perm(&perm)
+            }
+            // the last corner's twist follows from the others
+            Projection::CornerTwist => (0..7).fold(0, |acc, c| acc * 3 + (p[c * 3] % 3) as usize),
+            Projection::Edges(g) => (g * 4..g * 4 + 4).fold(0, |acc, e| acc * 24 + (p[24 + e * 2] - 24) as usize),
+        }
+    }
+
+    // moves needed to reach each value from any orientation of the solved cube;
+    // -1 for values that can't be reached
+    fn distances(self, perms: &[Perm]) -> Vec<i8> {
+        let mut table = vec![-1i8; self.size()];
+        let mut frontier = vec![];
+        for r in ROTATIONS.iter() {
+            let i = self.encode(r);
+            if table[i] < 0 {
+                table[i] = 0;
+                frontier.push(*r);
+            }
+        }
+        // searching back from solved, so the generators run backwards
+        let back: Vec<Perm> = perms.iter().map(inv).collect();
+        let mut depth = 0;
+        while !frontier.is_empty() {
+            depth += 1;
+            let mut next = vec![];
+            for s in frontier.iter() {
+                for b in back.iter() {
+                    let t = mul(s, b);
+                    let i = self.encode(&t);
+                    if table[i] < 0 {
+                        table[i] = depth;
+                        next.push(t);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        table
+    }
+}
+
+// turns about one axis commute, so only try them in one order, and never the
+// same layers twice in a row; as prune_move does for face turns
+fn prune_turn(prev: &BigMove, next: &BigMove) -> bool {
+    let axis = |m: &BigMove| m.face.to_usize().unwrap() / 2;
+    let key = |m: &BigMove| (m.face.to_usize().unwrap(), m.depth, m.wide);
+    axis(prev) == axis(next) && key(prev) >= key(next)
+}
+
+// IDA* solver like RestrictedSolver, for move sets with slice or wide turns;
+// again every power of each turn given is allowed
+pub struct SliceSolver {
+    turns: Vec<BigMove>,
+    perms: Vec<Perm>,
+    subgroup: Subgroup,
+    tables: Vec<(Projection, Vec<i8>)>,
+}
+
+impl SliceSolver {
+    pub fn new(turns: &[BigMove]) -> Self {
+        let mut all = vec![];
+        for m in turns.iter() {
+            for t in 1..4 {
+                let p = BigMove { turns: t, ..*m };
+                if !all.contains(&p) {
+                    all.push(p);
+                }
+            }
+        }
+        let turns = all.as_slice();
+        let perms: Vec<Perm> = turns.iter().map(turn_perm).collect();
+        let projections = [
+            Projection::CornerPerm,
+            Projection::CornerTwist,
+            Projection::Edges(0),
+            Projection::Edges(1),
+            Projection::Edges(2),
+        ];
+        SliceSolver {
+            turns: turns.to_vec(),
+            subgroup: Subgroup::generated_by(&perms),
+            tables: projections.iter().map(|&p| (p, p.distances(&perms))).collect(),
+            perms,
+        }
+    }
+
+    pub fn subgroup(&self) -> &Subgroup {
+        &self.subgroup
+    }
+
+    // shortest solution using only the allowed turns, ending in any orientation;
+    // None if there's none or it needs more than `max_depth` turns
+    pub fn solve(&self, cube: &CubeSequenceRepr, max_depth: usize) -> Option<Vec<BigMove>> {
+        if !self.subgroup.contains(cube) {
+            return None;
+        }
+        let (state, mut budget) = (to_perm(cube), Budget::new(None));
+        let mut moves = vec![];
+        let max_depth = max_depth.min(i8::MAX as usize) as i8;
+        for depth in self.h(&state)..=max_depth {
+            if self.search(&state, depth, &mut moves, &mut budget) {
+                return Some(moves);
+            }
+        }
+        None
+    }
+
+    fn h(&self, state: &Perm) -> i8 {
+        self.tables.iter().map(|(p, t)| t[p.encode(state)]).max().unwrap()
+    }
+
+    fn search(&self, state: &Perm, depth: i8, moves: &mut Vec<BigMove>, budget: &mut Budget) -> bool {
+        if budget.tick() {
+            return false;
+        }
+        if depth == 0 {
+            return ROTATIONS.contains(state);
+        }
+        for (m, p) in self.turns.iter().zip(self.perms.iter()) {
+            if moves.last().is_some_and(|last| prune_turn(last, m)) {
+                continue;
+            }
+            let next = mul(state, p);
+            if self.h(&next) < depth {
+                moves.push(*m);
+                if self.search(&next, depth - 1, moves, budget) {
+                    return true;
+                }
+                moves.pop();
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::heuristic::{ALL_MOVES, PHASE2_MOVES};
+    use crate::rubiks_cube::Rotation::*;
+
+    const RU: [Rotation; 6] = [R, Rr, R2, U, Ur, U2];
+
+    #[test]
+    fn test_subgroup_order() {
+        assert_eq!(Subgroup::new(&RU).order(), 73483200);
+        assert_eq!(Subgroup::new(&PHASE2_MOVES).order(), 19508428800);
+        assert_eq!(Subgroup::new(&ALL_MOVES).order(), 43252003274489856000);
+    }
+
+    #[test]
+    fn test_reachable() {
+        let ru = Subgroup::new(&RU);
+        assert!(ru.contains(&CubeSequenceRepr::from(&[R, U, Rr, U, R, U2, Rr])));
+        assert!(!ru.contains(&CubeSequenceRepr::from(&[F])));
+        // a single twisted corner isn't reachable by any moves at all
+        let mut twisted = CubeSequenceRepr::new();
+        twisted.co.0[0] = 1;
+        assert!(!Subgroup::new(&ALL_MOVES).contains(&twisted));
+    }
+
+    #[test]
+    fn test_solve_2gen() {
+        let solver = RestrictedSolver::new(&RU);
+        // Sune
+        let cube = CubeSequenceRepr::from(&[R, U, Rr, U, R, U2, Rr]);
+        let solution = solver.solve(&cube, 12).unwrap();
+        assert_eq!(solution.len(), 7);
+        assert!(solution.moves().iter().all(|r| RU.contains(r)));
+        assert_eq!(cube.multiply(&CubeSequenceRepr::from(solution.moves())), CubeSequenceRepr::new());
+        assert!(solver.solve(&CubeSequenceRepr::from(&[F]), 12).is_none());
+        assert_eq!(solver.solve(&cube, usize::MAX).unwrap().len(), 7);
+    }
+
+    #[test]
+    fn test_solve_generators() {
+        // just the generators, their other powers come along
+        let solver = RestrictedSolver::new(&[R, U]);
+        let cube = CubeSequenceRepr::from(&[R2]);
+        assert!(solver.subgroup().contains(&cube));
+        assert_eq!(solver.solve(&cube, 12).unwrap().moves(), &[R2]);
+        assert_eq!(solver.solve(&CubeSequenceRepr::from(&[R, U]), 12).unwrap().moves(), &[Ur, Rr]);
+        // M2 is R2 L2 and a rotation
+        let solver = SliceSolver::new(&parse_turns("M U").unwrap());
+        let solution = solver.solve(&CubeSequenceRepr::from(&[R2, L2]), 10).unwrap();
+        assert_eq!(solution.iter().map(turn_name).collect::<Vec<_>>(), ["M2"]);
+    }
+
+    #[test]
+    fn test_turn_perm() {
+        for &r in ALL_MOVES.iter() {
+            assert_eq!(turn_perm(&BigMove::outer(r)), to_perm(&CubeSequenceRepr::from(&[r])));
+        }
+        assert_eq!(ROTATIONS.len(), 24);
+    }
+
+    #[test]
+    fn test_parse_turns() {
+        let turns = parse_turns("M M' M2 E S' r Rw' 2R 3Fw").unwrap();
+        let names: Vec<String> = turns.iter().map(turn_name).collect();
+        assert_eq!(names, ["M", "M'", "M2", "E", "S'", "Rw", "Rw'", "M'", "3Fw"]);
+        assert!(parse_turns("4R").is_err());
+        assert!(parse_turns("Mw").is_err());
+    }
+
+    #[test]
+    fn test_solve_slices() {
+        let mu = parse_turns("M M' M2 U U' U2").unwrap();
+        let solver = SliceSolver::new(&mu);
+        // all 6 edges, the U corners' rotation and the M centres, with edge
+        // parity tied to the other two
+        assert_eq!(solver.subgroup().order(), 4 * 4 * 720 * 32 / 2);
+        // M U2 M' U2, in face turns: M is R L' x'
+        let cube = CubeSequenceRepr::from(&[R, Lr, B2, Rr, L, U2]);
+        assert!(!RestrictedSolver::new(&RU).subgroup().contains(&cube));
+        let solution = solver.solve(&cube, 10).unwrap();
+        assert_eq!(solution.len(), 4);
+        assert!(solution.iter().all(|m| mu.contains(m)));
+        let state = solution.iter().fold(to_perm(&cube), |s, m| mul(&s, &turn_perm(m)));
+        assert!(ROTATIONS.contains(&state));
+        // M alone is R L' and a rotation
+        assert_eq!(solver.solve(&CubeSequenceRepr::from(&[R, Lr]), 10).unwrap().len(), 1);
+        assert!(solver.solve(&CubeSequenceRepr::from(&[F]), 10).is_none());
+    }
+}
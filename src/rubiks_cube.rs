@@ -1,12 +1,20 @@
 use std::fmt::{Display, Formatter, Result};
 use std::ops::{Index, IndexMut};
+use std::str::FromStr;
 
-use crate::num_traits::ToPrimitive;
+use rand::seq::SliceRandom;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::color::ColorScheme;
+use crate::num_traits::{FromPrimitive, ToPrimitive};
 use crate::util::encode_perm;
+pub use crate::util::try_encode_perm;
 
 #[derive(FromPrimitive, ToPrimitive)]
 #[derive(Copy, Clone)]
-#[derive(PartialOrd, PartialEq)]
+#[derive(PartialOrd, PartialEq, Eq, Hash)]
 #[derive(Debug)]
 pub enum Rotation {
     U = 0,
@@ -48,16 +56,101 @@ impl Rotation {
     }
 
     pub fn is_ccw(&self) -> bool {
-        self.ge(&Rotation::Ur) && self.le(&Rotation::U2)
+        self.ge(&Rotation::Ur) && self.lt(&Rotation::U2)
     }
 
     pub fn is_180(&self) -> bool {
         self.ge(&Rotation::U2)
     }
+
+    // U/D, R/L and F/B each share an axis
+    pub fn axis(&self) -> u8 {
+        self.normal().to_u8().unwrap() / 2
+    }
+
+    // clockwise quarter turns: 1, 2 or 3
+    pub fn quarter_turns(&self) -> u8 {
+        if self.is_cw() {
+            1
+        } else if self.is_180() {
+            2
+        } else {
+            3
+        }
+    }
+
+    // turn `face` by the given number of clockwise quarter turns, None if it adds up to nothing
+    pub fn from_quarter_turns(face: Rotation, turns: u8) -> Option<Rotation> {
+        let base = face.normal().to_u8().unwrap();
+        match turns % 4 {
+            1 => num_traits::FromPrimitive::from_u8(base),
+            2 => num_traits::FromPrimitive::from_u8(base + 12),
+            3 => num_traits::FromPrimitive::from_u8(base + 6),
+            _ => None,
+        }
+    }
+}
+
+const FACE_NAMES: [&str; 6] = [
+    "U", "D", "R", "L", "F", "B"
+];
+
+impl Display for Rotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let suffix = if self.is_cw() {
+            ""
+        } else if self.is_180() {
+            "2"
+        } else {
+            "'"
+        };
+        write!(f, "{}{}", FACE_NAMES[self.normal().to_usize().unwrap()], suffix)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidMove(String),
+    InvalidFacelets(String),
+    InvalidColors(String),
+    Unsolvable(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ParseError::InvalidMove(m) => write!(f, "invalid move: {}", m),
+            ParseError::InvalidFacelets(m) => write!(f, "invalid facelets: {}", m),
+            ParseError::InvalidColors(m) => write!(f, "invalid colours: {}", m),
+            ParseError::Unsolvable(m) => write!(f, "unsolvable cube: {}", m),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// WCA notation: `R`, `R'` and `R2` (`R2'` and the typographic `R’` are accepted too)
+impl FromStr for Rotation {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let face = chars.next().and_then(Face::from_char).ok_or_else(|| ParseError::InvalidMove(s.to_string()))?;
+        let base = Rotation::from_u8(face.to_u8().unwrap()).unwrap();
+        let turns = match chars.as_str() {
+            "" => 1,
+            "'" | "’" => 3,
+            "2" | "2'" | "2’" => 2,
+            _ => return Err(ParseError::InvalidMove(s.to_string())),
+        };
+        Ok(Rotation::from_quarter_turns(base, turns).unwrap())
+    }
 }
 
 #[derive(Copy, Clone)]
 #[derive(FromPrimitive, ToPrimitive)]
+#[derive(PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Face {
     U = 0,
     D,
@@ -67,25 +160,39 @@ pub enum Face {
     B,
 }
 
+impl Face {
+    pub fn from_char(c: char) -> Option<Face> {
+        FACE_NAMES.iter().position(|n| n.starts_with(c)).and_then(Face::from_usize)
+    }
+
+    pub fn to_char(self) -> char {
+        FACE_NAMES[self.to_usize().unwrap()].chars().next().unwrap()
+    }
+}
+
 pub trait CubeRepr {
     fn rotate(&mut self, r: Rotation);
 }
 
 pub struct ArrayStruct<T>([T; 12]);
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EdgePerm(pub [u8; 12]);
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EdgeOrient(pub [bool; 12]);
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CornerPerm(pub [u8; 8]);
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CornerOrient(pub [u8; 8]);
 
-const CORNER_GROUP: [[usize; 4]; 6] = [
+pub(crate) const CORNER_GROUP: [[usize; 4]; 6] = [
     [0, 1, 2, 3],
     [7, 6, 5, 4],
     [2, 1, 5, 6],
@@ -94,7 +201,7 @@ const CORNER_GROUP: [[usize; 4]; 6] = [
     [1, 0, 4, 5],
 ];
 
-const EDGE_GROUP: [[usize; 4]; 6] = [
+pub(crate) const EDGE_GROUP: [[usize; 4]; 6] = [
     [0, 1, 2, 3],
     [11, 10, 9, 8],
     [1, 5, 9, 6],
@@ -103,6 +210,18 @@ const EDGE_GROUP: [[usize; 4]; 6] = [
     [0, 4, 8, 5],
 ];
 
+// faces of each corner and edge piece, in orientation order
+pub(crate) const CORNER_MAP: [[Face; 3]; 8] = [
+    [Face::U, Face::L, Face::B], [Face::U, Face::B, Face::R], [Face::U, Face::R, Face::F], [Face::U, Face::F, Face::L],
+    [Face::D, Face::B, Face::L], [Face::D, Face::R, Face::B], [Face::D, Face::F, Face::R], [Face::D, Face::L, Face::F]
+];
+
+pub(crate) const EDGE_MAP: [[Face; 2]; 12] = [
+    [Face::U, Face::B], [Face::U, Face::R], [Face::U, Face::F], [Face::U, Face::L],
+    [Face::L, Face::B], [Face::R, Face::B], [Face::R, Face::F], [Face::L, Face::F],
+    [Face::D, Face::B], [Face::D, Face::R], [Face::D, Face::F], [Face::D, Face::L]
+];
+
 fn rotate_seq<T: Sized + Copy>(seq: &mut [T], r: Rotation, group: &[usize; 4]) {
     let dir = r.is_cw();
     if r.is_180() {
@@ -131,35 +250,66 @@ fn rotate_ccw<T: Sized + Copy>(seq: &mut [T], group: &[usize; 4]) {
     seq[group[3]] = tmp;
 }
 
+impl Default for EdgePerm {
+    fn default() -> Self {
+        EdgePerm::new()
+    }
+}
+
 impl EdgePerm {
     pub fn new() -> Self {
-        EdgePerm {
-            0: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
-        }
+        EdgePerm([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11])
+    }
+
+    // every edge shows up exactly once
+    pub fn is_valid(&self) -> bool {
+        try_encode_perm(&self.0).is_some()
+    }
+}
+
+impl Default for EdgeOrient {
+    fn default() -> Self {
+        EdgeOrient::new()
     }
 }
 
 impl EdgeOrient {
     pub fn new() -> Self {
-        EdgeOrient {
-            0: [false; 12]
-        }
+        EdgeOrient([false; 12])
+    }
+}
+
+impl Default for CornerPerm {
+    fn default() -> Self {
+        CornerPerm::new()
     }
 }
 
 impl CornerPerm {
     pub fn new() -> Self {
-        CornerPerm {
-            0: [0, 1, 2, 3, 4, 5, 6, 7],
-        }
+        CornerPerm([0, 1, 2, 3, 4, 5, 6, 7])
+    }
+
+    // every corner shows up exactly once
+    pub fn is_valid(&self) -> bool {
+        try_encode_perm(&self.0).is_some()
+    }
+}
+
+impl Default for CornerOrient {
+    fn default() -> Self {
+        CornerOrient::new()
     }
 }
 
 impl CornerOrient {
     pub fn new() -> Self {
-        CornerOrient {
-            0: [0; 8]
-        }
+        CornerOrient([0; 8])
+    }
+
+    // twists of 0 to 2 that add up to a multiple of 3
+    pub fn is_valid(&self) -> bool {
+        self.0.iter().all(|&t| t < 3) && self.0.iter().sum::<u8>() % 3 == 0
     }
 }
 
@@ -173,11 +323,9 @@ impl CubeRepr for EdgeOrient {
     fn rotate(&mut self, r: Rotation) {
         let norm = r.normal();
         rotate_seq(&mut self.0, r, &EDGE_GROUP[norm.to_usize().unwrap()]);
-        if !r.is_180() {
-            if norm == Rotation::R || norm == Rotation::L {
-                for &i in EDGE_GROUP[norm.to_usize().unwrap()].iter() {
-                    self.0[i] = !self.0[i];
-                }
+        if !r.is_180() && (norm == Rotation::R || norm == Rotation::L) {
+            for &i in EDGE_GROUP[norm.to_usize().unwrap()].iter() {
+                self.0[i] = !self.0[i];
             }
         }
     }
@@ -193,24 +341,28 @@ impl CubeRepr for CornerOrient {
     fn rotate(&mut self, r: Rotation) {
         let norm = r.normal();
         rotate_seq(&mut self.0, r, &CORNER_GROUP[norm.to_usize().unwrap()]);
-        if !r.is_180() {
-            if norm != Rotation::U && norm != Rotation::D {
-                for i in 0..4 {
-                    let idx = CORNER_GROUP[norm.to_usize().unwrap()][i];
-                    // 1, 2, 1, 2
-                    self.0[idx] = (self.0[idx] + 1 + (i as u8 % 2)) % 3;
-                }
+        if !r.is_180() && norm != Rotation::U && norm != Rotation::D {
+            for (i, &idx) in CORNER_GROUP[norm.to_usize().unwrap()].iter().enumerate() {
+                // 1, 2, 1, 2
+                self.0[idx] = (self.0[idx] + 1 + (i as u8 % 2)) % 3;
             }
         }
     }
 }
 
 #[derive(Debug)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+// A real cube as long as it comes from the checked constructors, so the solvers
+// and encoders can index their tables with the pieces. The fields are still
+// public for this release only; writing them skips the checks
 pub struct CubeSequenceRepr {
+    #[deprecated(note = "read it with `ep()`, build cubes with `CubeSequenceRepr::from_parts`")]
     pub ep: EdgePerm,
+    #[deprecated(note = "read it with `cp()`, build cubes with `CubeSequenceRepr::from_parts`")]
     pub cp: CornerPerm,
+    #[deprecated(note = "read it with `eo()`, build cubes with `CubeSequenceRepr::from_parts`")]
     pub eo: EdgeOrient,
+    #[deprecated(note = "read it with `co()`, build cubes with `CubeSequenceRepr::from_parts`")]
     pub co: CornerOrient,
 }
 
@@ -223,6 +375,12 @@ impl CubeRepr for CubeSequenceRepr {
     }
 }
 
+impl Default for CubeSequenceRepr {
+    fn default() -> Self {
+        CubeSequenceRepr::new()
+    }
+}
+
 impl CubeSequenceRepr {
     pub fn new() -> Self {
         CubeSequenceRepr {
@@ -241,47 +399,164 @@ impl CubeSequenceRepr {
         repr
     }
 
-    pub fn visualize(&self, buf: &mut ColoredCube) {
-        const CORNER_MAP: [[Face; 3]; 8] = [
-            [Face::U, Face::L, Face::B], [Face::U, Face::B, Face::R], [Face::U, Face::R, Face::F], [Face::U, Face::F, Face::L],
-            [Face::D, Face::B, Face::L], [Face::D, Face::R, Face::B], [Face::D, Face::F, Face::R], [Face::D, Face::L, Face::F]
-        ];
-        const EDGE_MAP: [[Face; 2]; 12] = [
-            [Face::U, Face::B], [Face::U, Face::R], [Face::U, Face::F], [Face::U, Face::L],
-            [Face::L, Face::B], [Face::R, Face::B], [Face::R, Face::F], [Face::L, Face::F],
-            [Face::D, Face::B], [Face::D, Face::R], [Face::D, Face::F], [Face::D, Face::L]
-        ];
-        const CORNERS: [[usize; 2]; 8] = [
-            [0, 0], [0, 2], [2, 2], [2, 0],
-            [2, 0], [2, 2], [0, 2], [0, 0]
-        ];
-        const EDGES: [[usize; 2]; 12] = [
-            [0, 1], [1, 2], [2, 1], [1, 0],
-            [1, 0], [1, 2], [1, 0], [1, 2],
-            [2, 1], [1, 2], [0, 1], [1, 0]
-        ];
-        // color all corners
+    // a cube from its pieces, if they make up one that can be solved
+    pub fn from_parts(ep: EdgePerm, cp: CornerPerm, eo: EdgeOrient, co: CornerOrient) -> std::result::Result<Self, ParseError> {
+        let cube = CubeSequenceRepr { ep, cp, eo, co };
+        cube.validate()?;
+        Ok(cube)
+    }
+
+    pub fn ep(&self) -> &EdgePerm {
+        &self.ep
+    }
+
+    pub fn cp(&self) -> &CornerPerm {
+        &self.cp
+    }
+
+    pub fn eo(&self) -> &EdgeOrient {
+        &self.eo
+    }
+
+    pub fn co(&self) -> &CornerOrient {
+        &self.co
+    }
+
+    // state reached by applying `other` on top of `self`
+    pub fn multiply(&self, other: &CubeSequenceRepr) -> Self {
+        let mut res = CubeSequenceRepr::new();
+        for i in 0..12 {
+            let from = other.ep.0[i] as usize;
+            res.ep.0[i] = self.ep.0[from];
+            res.eo.0[i] = self.eo.0[from] ^ other.eo.0[i];
+        }
         for i in 0..8 {
-            buf[CORNER_MAP[i][0]][CORNERS[i][0]][CORNERS[i][1]] = CORNER_MAP[self.cp.0[i] as usize][self.co.0[i] as usize];
+            let from = other.cp.0[i] as usize;
+            res.cp.0[i] = self.cp.0[from];
+            res.co.0[i] = (self.co.0[from] + other.co.0[i]) % 3;
         }
-        for i in 0..4 {
-            buf[CORNER_MAP[i][1]][0][0] = CORNER_MAP[self.cp.0[i] as usize][((self.co.0[i] + 1) % 3) as usize];
-            buf[CORNER_MAP[i][2]][0][2] = CORNER_MAP[self.cp.0[i] as usize][((self.co.0[i] + 2) % 3) as usize];
+        res
+    }
+
+    // the state that undoes `self`, i.e. `self.multiply(&self.inverse())` is solved
+    pub fn inverse(&self) -> Self {
+        let mut res = CubeSequenceRepr::new();
+        for i in 0..12 {
+            let to = self.ep.0[i] as usize;
+            res.ep.0[to] = i as u8;
+            res.eo.0[to] = self.eo.0[i];
+        }
+        for i in 0..8 {
+            let to = self.cp.0[i] as usize;
+            res.cp.0[to] = i as u8;
+            res.co.0[to] = (3 - self.co.0[i]) % 3;
+        }
+        res
+    }
+
+    // a uniformly random reachable state
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut res = CubeSequenceRepr::new();
+        res.ep.0.shuffle(rng);
+        res.cp.0.shuffle(rng);
+        if res.ep.parity() != res.cp.parity() {
+            res.cp.0.swap(0, 1);
+        }
+        for i in 0..11 {
+            res.eo.0[i] = rng.gen();
+        }
+        res.eo.0[11] = res.eo.0[..11].iter().fold(false, |acc, &x| acc ^ x);
+        for i in 0..7 {
+            res.co.0[i] = rng.gen_range(0..3);
+        }
+        res.co.0[7] = (3 - res.co.0[..7].iter().sum::<u8>() % 3) % 3;
+        res
+    }
+
+    // check that every piece shows up once and the state can be reached by turning faces
+    pub fn validate(&self) -> std::result::Result<(), ParseError> {
+        if !self.ep.is_valid() {
+            return Err(ParseError::Unsolvable("edges are not a permutation".to_string()));
+        }
+        if !self.cp.is_valid() {
+            return Err(ParseError::Unsolvable("corners are not a permutation".to_string()));
+        }
+        if !self.co.is_valid() {
+            return Err(ParseError::Unsolvable("twisted corner".to_string()));
+        }
+        if self.eo.0.iter().filter(|&&f| f).count() % 2 != 0 {
+            return Err(ParseError::Unsolvable("flipped edge".to_string()));
+        }
+        if self.ep.parity() != self.cp.parity() {
+            return Err(ParseError::Unsolvable("parity error".to_string()));
         }
-        for i in 4..8 {
-            buf[CORNER_MAP[i][1]][2][2] = CORNER_MAP[self.cp.0[i] as usize][((self.co.0[i] + 1) % 3) as usize];
-            buf[CORNER_MAP[i][2]][2][0] = CORNER_MAP[self.cp.0[i] as usize][((self.co.0[i] + 2) % 3) as usize];
+        Ok(())
+    }
+
+    // 54 facelets in the usual U, R, F, D, L, B order, each face read row by row as
+    // laid out in the net printed by ColoredCube
+    pub fn from_facelets(s: &str) -> std::result::Result<Self, ParseError> {
+        let chars: Vec<char> = s.trim().chars().collect();
+        if chars.len() != 54 {
+            return Err(ParseError::InvalidFacelets(format!("expected 54 facelets, got {}", chars.len())));
+        }
+        let mut buf: ColoredCube = ColoredCube::new();
+        for (i, &f) in FACELET_ORDER.iter().enumerate() {
+            for j in 0..9 {
+                let c = chars[i * 9 + j];
+                buf[f][j / 3][j % 3] = Face::from_char(c)
+                    .ok_or_else(|| ParseError::InvalidFacelets(format!("unknown facelet '{}'", c)))?;
+            }
+            if buf[f][1][1] != f {
+                return Err(ParseError::InvalidFacelets(format!("wrong centre on face {}", FACE_NAMES[f.to_usize().unwrap()])));
+            }
+        }
+        let mut res = CubeSequenceRepr::new();
+        for i in 0..8 {
+            let colors: Vec<Face> = corner_stickers(i).iter().map(|&(f, r, c)| buf[f][r][c]).collect();
+            let (p, o) = (0..8).flat_map(|p| (0..3).map(move |o| (p, o)))
+                .find(|&(p, o)| (0..3).all(|k| CORNER_MAP[p][(o + k) % 3] == colors[k]))
+                .ok_or_else(|| ParseError::InvalidFacelets(format!("no such corner at position {}", i)))?;
+            res.cp.0[i] = p as u8;
+            res.co.0[i] = o as u8;
+        }
+        for i in 0..12 {
+            let colors: Vec<Face> = edge_stickers(i).iter().map(|&(f, r, c)| buf[f][r][c]).collect();
+            let (p, o) = (0..12).flat_map(|p| (0..2).map(move |o| (p, o)))
+                .find(|&(p, o)| (0..2).all(|k| EDGE_MAP[p][(o + k) % 2] == colors[k]))
+                .ok_or_else(|| ParseError::InvalidFacelets(format!("no such edge at position {}", i)))?;
+            res.ep.0[i] = p as u8;
+            res.eo.0[i] = o == 1;
+        }
+        res.validate()?;
+        Ok(res)
+    }
+
+    pub fn to_facelets(&self) -> String {
+        let mut buf = ColoredCube::new();
+        self.visualize(&mut buf);
+        let mut res = String::with_capacity(54);
+        for &f in FACELET_ORDER.iter() {
+            for row in buf[f].iter() {
+                for c in row.iter() {
+                    res.push_str(FACE_NAMES[c.to_usize().unwrap()]);
+                }
+            }
+        }
+        res
+    }
+
+    pub fn visualize(&self, buf: &mut ColoredCube) {
+        // color all corners
+        for i in 0..8 {
+            for (k, &(f, r, c)) in corner_stickers(i).iter().enumerate() {
+                buf[f][r][c] = CORNER_MAP[self.cp.0[i] as usize][(self.co.0[i] as usize + k) % 3];
+            }
         }
         // color all edges
         for i in 0..12 {
-            buf[EDGE_MAP[i][0]][EDGES[i][0] as usize][EDGES[i][1] as usize] = EDGE_MAP[self.ep.0[i] as usize][self.eo.0[i] as usize];
-            if i < 4 {
-                buf[EDGE_MAP[i][1]][0][1] = EDGE_MAP[self.ep.0[i] as usize][!self.eo.0[i] as usize];
-            } else if i < 8 {
-                // 1,2 1,0 1,2 1,0
-                buf[EDGE_MAP[i][1]][1][2 - 2 * (i % 2)] = EDGE_MAP[self.ep.0[i] as usize][!self.eo.0[i] as usize];
-            } else {
-                buf[EDGE_MAP[i][1]][2][1] = EDGE_MAP[self.ep.0[i] as usize][!self.eo.0[i] as usize];
+            for (k, &(f, r, c)) in edge_stickers(i).iter().enumerate() {
+                buf[f][r][c] = EDGE_MAP[self.ep.0[i] as usize][(self.eo.0[i] as usize + k) % 2];
             }
         }
         // color all middles
@@ -291,82 +566,141 @@ impl CubeSequenceRepr {
     }
 }
 
-// visualizer
-pub struct ColoredCube {
-    pub dat: [[[Face; 3]; 3]; 6]
+// face order of facelet strings
+const FACELET_ORDER: [Face; 6] = [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+// (face, row, col) of each sticker of the corner at position `i`, in orientation order
+pub(crate) fn corner_stickers(i: usize) -> [(Face, usize, usize); 3] {
+    const CORNERS: [[usize; 2]; 8] = [
+        [0, 0], [0, 2], [2, 2], [2, 0],
+        [2, 0], [2, 2], [0, 2], [0, 0]
+    ];
+    let (a, b) = if i < 4 { ((0, 0), (0, 2)) } else { ((2, 2), (2, 0)) };
+    [
+        (CORNER_MAP[i][0], CORNERS[i][0], CORNERS[i][1]),
+        (CORNER_MAP[i][1], a.0, a.1),
+        (CORNER_MAP[i][2], b.0, b.1),
+    ]
 }
 
-impl Index<usize> for ColoredCube {
-    type Output = [[Face; 3]; 3];
+// (face, row, col) of each sticker of the edge at position `i`, in orientation order
+pub(crate) fn edge_stickers(i: usize) -> [(Face, usize, usize); 2] {
+    const EDGES: [[usize; 2]; 12] = [
+        [0, 1], [1, 2], [2, 1], [1, 0],
+        [1, 0], [1, 2], [1, 0], [1, 2],
+        [2, 1], [1, 2], [0, 1], [1, 0]
+    ];
+    let second = if i < 4 {
+        (0, 1)
+    } else if i < 8 {
+        // 1,2 1,0 1,2 1,0
+        (1, 2 - 2 * (i % 2))
+    } else {
+        (2, 1)
+    };
+    [
+        (EDGE_MAP[i][0], EDGES[i][0], EDGES[i][1]),
+        (EDGE_MAP[i][1], second.0, second.1),
+    ]
+}
+
+// visualizer, N stickers to a side
+pub struct ColoredCube<const N: usize = 3> {
+    pub dat: [[[Face; N]; N]; 6],
+    // how faces are coloured when drawn
+    pub scheme: ColorScheme,
+}
+
+impl<const N: usize> Index<usize> for ColoredCube<N> {
+    type Output = [[Face; N]; N];
 
     fn index(&self, index: usize) -> &Self::Output {
         &self.dat[index]
     }
 }
 
-impl IndexMut<usize> for ColoredCube {
+impl<const N: usize> IndexMut<usize> for ColoredCube<N> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         &mut self.dat[index]
     }
 }
 
-impl Index<Face> for ColoredCube {
-    type Output = [[Face; 3]; 3];
+impl<const N: usize> Index<Face> for ColoredCube<N> {
+    type Output = [[Face; N]; N];
 
     fn index(&self, index: Face) -> &Self::Output {
         &self[index.to_usize().unwrap()]
     }
 }
 
-impl IndexMut<Face> for ColoredCube {
+impl<const N: usize> IndexMut<Face> for ColoredCube<N> {
     fn index_mut(&mut self, index: Face) -> &mut Self::Output {
         self.index_mut(index.to_usize().unwrap())
     }
 }
 
-const COLOR_MAP: [&'static str; 6] = [
-    "W", "Y", "R", "O", "G", "B"
-];
-
-const SEP: &'static str = " ";
+const SEP: &str = " ";
 
-impl Display for ColoredCube {
+impl<const N: usize> Display for ColoredCube<N> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        for i in 0..3 {
-            for _ in 0..3 { write!(f, "{}", SEP)?; }
-            for j in 0..3 { write!(f, "{}", COLOR_MAP[self.dat[Face::U.to_usize().unwrap()][i][j].to_usize().unwrap()])?; }
-            write!(f, "\n")?;
+        for i in 0..N {
+            for _ in 0..N { write!(f, "{}", SEP)?; }
+            for j in 0..N { write!(f, "{}", self.scheme.color(self.dat[Face::U.to_usize().unwrap()][i][j]))?; }
+            writeln!(f)?;
         }
-        for j in 0..3 {
+        for j in 0..N {
             for i in [Face::L, Face::F, Face::R, Face::B].iter() {
-                for k in 0..3 {
-                    write!(f, "{}", COLOR_MAP[self.dat[i.to_usize().unwrap()][j][k].to_usize().unwrap()])?;
+                for k in 0..N {
+                    write!(f, "{}", self.scheme.color(self.dat[i.to_usize().unwrap()][j][k]))?;
                 }
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
-        for i in 0..3 {
-            for _ in 0..3 { write!(f, "{}", SEP)?; }
-            for j in 0..3 { write!(f, "{}", COLOR_MAP[self.dat[Face::D.to_usize().unwrap()][i][j].to_usize().unwrap()])?; }
-            write!(f, "\n")?;
+        for i in 0..N {
+            for _ in 0..N { write!(f, "{}", SEP)?; }
+            for j in 0..N { write!(f, "{}", self.scheme.color(self.dat[Face::D.to_usize().unwrap()][i][j]))?; }
+            writeln!(f)?;
         }
         Ok(())
     }
 }
 
-impl ColoredCube {
+impl<const N: usize> Default for ColoredCube<N> {
+    fn default() -> Self {
+        ColoredCube::new()
+    }
+}
+
+impl<const N: usize> ColoredCube<N> {
     pub fn new() -> Self {
+        ColoredCube::with_scheme(ColorScheme::default())
+    }
+
+    pub fn with_scheme(scheme: ColorScheme) -> Self {
         ColoredCube {
-            dat: [[[Face::U; 3]; 3]; 6]
+            dat: [[[Face::U; N]; N]; 6],
+            scheme,
         }
     }
-}
 
-// various encoders
-pub fn ep_encode(repr: &EdgePerm) -> usize {
-    encode_perm(&repr.0)
+    // the stickers laid out as the cross-shaped net printed by Display, 3N rows by 4N columns
+    pub fn net(&self) -> Vec<Vec<Option<Face>>> {
+        let mut res = vec![vec![None; 4 * N]; 3 * N];
+        let placement = [(Face::U, 0, 1), (Face::L, 1, 0), (Face::F, 1, 1), (Face::R, 1, 2), (Face::B, 1, 3), (Face::D, 2, 1)];
+        for &(face, row, col) in placement.iter() {
+            for i in 0..N {
+                for j in 0..N {
+                    res[row * N + i][col * N + j] = Some(self[face][i][j]);
+                }
+            }
+        }
+        res
+    }
 }
 
+// various encoders; all but eo_encode trust their input and may panic or return
+// out of range indices for pieces that aren't valid, so outside the crate it's
+// the try_ versions below. The others stay public for this release only
 pub fn eo_encode(repr: &EdgeOrient) -> usize {
     let mut res: usize = 0;
     for i in 0..11 {
@@ -375,14 +709,100 @@ pub fn eo_encode(repr: &EdgeOrient) -> usize {
     res
 }
 
+#[deprecated(note = "use `try_ep_encode`")]
+pub fn ep_encode(repr: &EdgePerm) -> usize {
+    encode_perm(&repr.0)
+}
+
+#[deprecated(note = "use `try_cp_encode`")]
 pub fn cp_encode(repr: &CornerPerm) -> usize {
     encode_perm(&repr.0)
 }
 
+#[deprecated(note = "use `try_co_encode`")]
 pub fn co_encode(repr: &CornerOrient) -> usize {
     let mut res = 0;
     for i in 0..7 {
         res = res * 3 + (repr.0[i] as usize);
     }
     res
-}
\ No newline at end of file
+}
+
+pub fn try_ep_encode(repr: &EdgePerm) -> Option<usize> {
+    try_encode_perm(&repr.0)
+}
+
+pub fn try_cp_encode(repr: &CornerPerm) -> Option<usize> {
+    try_encode_perm(&repr.0)
+}
+
+pub fn try_co_encode(repr: &CornerOrient) -> Option<usize> {
+    if repr.is_valid() { Some(co_encode(repr)) } else { None }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const SOLVED: &str = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+    #[test]
+    fn test_facelets() {
+        assert_eq!(CubeSequenceRepr::new().to_facelets(), SOLVED);
+        assert_eq!(CubeSequenceRepr::from(&[Rotation::U]).to_facelets(),
+                   "UUUUUUUUUBBBRRRRRRRRRFFFFFFDDDDDDDDDFFFLLLLLLLLLBBBBBB");
+        assert_eq!(CubeSequenceRepr::from(&[Rotation::R]).to_facelets(),
+                   "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB");
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            let cube = CubeSequenceRepr::random(&mut rng);
+            assert_eq!(cube.validate(), Ok(()));
+            assert_eq!(CubeSequenceRepr::from_facelets(&cube.to_facelets()), Ok(cube));
+        }
+    }
+
+    #[test]
+    fn test_invalid_facelets() {
+        assert!(CubeSequenceRepr::from_facelets("UUU").is_err());
+        assert!(CubeSequenceRepr::from_facelets(&SOLVED.replace('B', "X")).is_err());
+        // swap two stickers of the UFR corner: twisted, and no longer a real piece
+        let mut twisted: Vec<char> = SOLVED.chars().collect();
+        twisted.swap(8, 9);
+        assert!(CubeSequenceRepr::from_facelets(&twisted.iter().collect::<String>()).is_err());
+        // swap the two stickers of the UF edge
+        let mut flipped: Vec<char> = SOLVED.chars().collect();
+        flipped.swap(7, 19);
+        assert_eq!(CubeSequenceRepr::from_facelets(&flipped.iter().collect::<String>()),
+                   Err(ParseError::Unsolvable("flipped edge".to_string())));
+    }
+
+    #[test]
+    fn test_try_encode() {
+        let cube = CubeSequenceRepr::from(&[Rotation::R, Rotation::U, Rotation::Fr]);
+        assert_eq!(try_ep_encode(&cube.ep), Some(encode_perm(&cube.ep.0)));
+        assert_eq!(try_cp_encode(&cube.cp), Some(cp_encode(&cube.cp)));
+        assert_eq!(try_co_encode(&cube.co), Some(co_encode(&cube.co)));
+        assert_eq!(try_ep_encode(&EdgePerm([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 200])), None);
+        assert_eq!(try_cp_encode(&CornerPerm([0; 8])), None);
+        assert_eq!(try_co_encode(&CornerOrient([3; 8])), None);
+    }
+
+    #[test]
+    fn test_from_parts() {
+        let cube = CubeSequenceRepr::from(&[Rotation::R, Rotation::U, Rotation::Fr]);
+        assert_eq!(CubeSequenceRepr::from_parts(cube.ep, cube.cp, cube.eo, cube.co), Ok(cube));
+        let mut ep = cube.ep;
+        ep.0[0] = 12;
+        assert!(CubeSequenceRepr::from_parts(ep, cube.cp, cube.eo, cube.co).is_err());
+        let mut co = cube.co;
+        co.0[3] = 3;
+        assert!(CubeSequenceRepr::from_parts(cube.ep, cube.cp, cube.eo, co).is_err());
+        // a single corner swap is a real state of the pieces, just not one you can reach
+        let mut cp = cube.cp;
+        cp.0.swap(0, 1);
+        assert_eq!(CubeSequenceRepr::from_parts(cube.ep, cp, cube.eo, cube.co),
+                   Err(ParseError::Unsolvable("parity error".to_string())));
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt::{Display, Formatter, Result};
+use std::str::FromStr;
+
+use crate::rubiks_cube::{ParseError, Rotation};
+
+// A sequence of face turns, e.g. a scramble, an algorithm or a solution
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct MoveSequence(pub Vec<Rotation>);
+
+impl MoveSequence {
+    pub fn new() -> Self {
+        MoveSequence(vec![])
+    }
+
+    pub fn moves(&self) -> &[Rotation] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn push(&mut self, r: Rotation) {
+        self.0.push(r);
+    }
+
+    // the sequence undoing this one
+    pub fn inverse(&self) -> MoveSequence {
+        MoveSequence(self.0.iter().rev().map(Rotation::reverse).collect())
+    }
+
+    // merge consecutive turns of the same face and cancel what adds up to nothing,
+    // looking through turns of the opposite face since those commute (`U D U'` -> `D`)
+    pub fn simplify(&self) -> MoveSequence {
+        let mut res: Vec<Rotation> = Vec::with_capacity(self.0.len());
+        for &r in self.0.iter() {
+            let mut i = res.len();
+            let mut merged = false;
+            while i > 0 && res[i - 1].axis() == r.axis() {
+                i -= 1;
+                if res[i].normal() == r.normal() {
+                    match Rotation::from_quarter_turns(r, res[i].quarter_turns() + r.quarter_turns()) {
+                        Some(m) => res[i] = m,
+                        None => { res.remove(i); }
+                    }
+                    merged = true;
+                    break;
+                }
+            }
+            if !merged {
+                res.push(r);
+            }
+        }
+        MoveSequence(res)
+    }
+}
+
+impl From<Vec<Rotation>> for MoveSequence {
+    fn from(moves: Vec<Rotation>) -> Self {
+        MoveSequence(moves)
+    }
+}
+
+impl From<&[Rotation]> for MoveSequence {
+    fn from(moves: &[Rotation]) -> Self {
+        MoveSequence(moves.to_vec())
+    }
+}
+
+// moves may be separated by whitespace or written back to back, e.g. `RUR'U'`
+impl FromStr for MoveSequence {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut res = MoveSequence::new();
+        let mut token = String::new();
+        for c in s.chars().chain(std::iter::once(' ')) {
+            let modifier = c == '2' || c == '\'' || c == '’';
+            if !token.is_empty() && !modifier {
+                res.push(token.parse()?);
+                token.clear();
+            }
+            if !c.is_whitespace() {
+                if modifier && token.is_empty() {
+                    return Err(ParseError::InvalidMove(c.to_string()));
+                }
+                token.push(c);
+            }
+        }
+        Ok(res)
+    }
+}
+
+impl Display for MoveSequence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for (i, r) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", r)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rubiks_cube::CubeSequenceRepr;
+    use crate::rubiks_cube::Rotation::*;
+
+    fn simplify(moves: &[Rotation]) -> Vec<Rotation> {
+        MoveSequence::from(moves).simplify().0
+    }
+
+    #[test]
+    fn test_simplify() {
+        assert_eq!(simplify(&[U, U]), vec![U2]);
+        assert_eq!(simplify(&[U, Ur]), vec![]);
+        assert_eq!(simplify(&[U2, U]), vec![Ur]);
+        assert_eq!(simplify(&[U, D, Ur]), vec![D]);
+        assert_eq!(simplify(&[R, U, D, Dr, Ur, Rr]), vec![]);
+        assert_eq!(simplify(&[F, F2, R, L, R]), vec![Fr, R2, L]);
+        // moves on other axes block cancellation
+        assert_eq!(simplify(&[U, R, Ur]), vec![U, R, Ur]);
+    }
+
+    #[test]
+    fn test_simplify_keeps_state() {
+        let moves = [D, L2, Br, D, L, D2, Br, D, R2, F, F2, R2, U, R2, D2, R2, Ur, F2, Dr, U, U2, D];
+        let simplified = simplify(&moves);
+        assert!(simplified.len() < moves.len());
+        assert_eq!(CubeSequenceRepr::from(&simplified), CubeSequenceRepr::from(&moves));
+    }
+
+    #[test]
+    fn test_parse() {
+        let seq: MoveSequence = "R U R' U' F2".parse().unwrap();
+        assert_eq!(seq.0, vec![R, U, Rr, Ur, F2]);
+        assert_eq!("RUR'U'F2".parse::<MoveSequence>().unwrap(), seq);
+        assert_eq!("  D2' B’\n".parse::<MoveSequence>().unwrap().0, vec![D2, Br]);
+        assert!("".parse::<MoveSequence>().unwrap().is_empty());
+        assert!("R X".parse::<MoveSequence>().is_err());
+        assert!("R '".parse::<MoveSequence>().is_err());
+        assert_eq!(seq.inverse().to_string(), "F2 U R U' R'");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(MoveSequence::from(vec![R, U, Rr, Ur, F2]).to_string(), "R U R' U' F2");
+    }
+}
@@ -0,0 +1,131 @@
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::rubiks_cube::{CornerOrient, CornerPerm, CubeSequenceRepr, EdgeOrient, EdgePerm, Rotation};
+use crate::sequence::MoveSequence;
+
+// serde support, behind the `serde` feature
+//
+// Moves are written in WCA notation ("R2", "U' F"). Cubes are written as
+// facelet strings, and read back from either a facelet string or the raw
+// cubie arrays ({"ep": [...], "cp": [...], "eo": [...], "co": [...]});
+// use `#[serde(with = "cube_solver::serialize::raw")]` to write the arrays.
+
+impl Serialize for Rotation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rotation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for MoveSequence {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MoveSequence {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawCube {
+    ep: EdgePerm,
+    cp: CornerPerm,
+    eo: EdgeOrient,
+    co: CornerOrient,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CubeInput {
+    Facelets(String),
+    Raw(RawCube),
+}
+
+impl Serialize for CubeSequenceRepr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_facelets())
+    }
+}
+
+impl<'de> Deserialize<'de> for CubeSequenceRepr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match CubeInput::deserialize(deserializer)? {
+            CubeInput::Facelets(s) => CubeSequenceRepr::from_facelets(&s),
+            CubeInput::Raw(raw) => CubeSequenceRepr::from_parts(raw.ep, raw.cp, raw.eo, raw.co),
+        }.map_err(D::Error::custom)
+    }
+}
+
+// CubeSequenceRepr as the raw cubie arrays
+pub mod raw {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(cube: &CubeSequenceRepr, serializer: S) -> Result<S::Ok, S::Error> {
+        RawCube { ep: cube.ep, cp: cube.cp, eo: cube.eo, co: cube.co }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CubeSequenceRepr, D::Error> {
+        CubeSequenceRepr::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::kociemba::KociembaSolver;
+    use crate::rubiks_cube::Face;
+
+    #[test]
+    fn test_moves() {
+        assert_eq!(serde_json::to_string(&Rotation::Rr).unwrap(), "\"R'\"");
+        assert_eq!(serde_json::from_str::<Rotation>("\"U2\"").unwrap(), Rotation::U2);
+        assert!(serde_json::from_str::<Rotation>("\"X\"").is_err());
+        let seq: MoveSequence = "R U R' U'".parse().unwrap();
+        assert_eq!(serde_json::to_string(&seq).unwrap(), "\"R U R' U'\"");
+        assert_eq!(serde_json::from_str::<MoveSequence>("\"R U R' U'\"").unwrap(), seq);
+        assert_eq!(serde_json::to_string(&Face::F).unwrap(), "\"F\"");
+    }
+
+    #[test]
+    fn test_cube() {
+        #[derive(Serialize, Deserialize)]
+        struct Stored {
+            #[serde(with = "raw")]
+            cube: CubeSequenceRepr,
+        }
+
+        let cube = CubeSequenceRepr::from(&[Rotation::R, Rotation::U, Rotation::Fr]);
+        let json = serde_json::to_string(&cube).unwrap();
+        assert_eq!(json, format!("\"{}\"", cube.to_facelets()));
+        assert_eq!(serde_json::from_str::<CubeSequenceRepr>(&json).unwrap(), cube);
+
+        let json = serde_json::to_string(&Stored { cube }).unwrap();
+        assert!(json.starts_with("{\"cube\":{\"ep\":["));
+        assert_eq!(serde_json::from_str::<Stored>(&json).unwrap().cube, cube);
+
+        // both forms are accepted either way, and checked
+        let mut raw = RawCube { ep: cube.ep, cp: cube.cp, eo: cube.eo, co: cube.co };
+        assert_eq!(serde_json::from_str::<CubeSequenceRepr>(&serde_json::to_string(&raw).unwrap()).unwrap(), cube);
+        raw.co.0[0] = (raw.co.0[0] + 1) % 3;
+        assert!(serde_json::from_str::<CubeSequenceRepr>(&serde_json::to_string(&raw).unwrap()).is_err());
+        assert!(serde_json::from_str::<CubeSequenceRepr>("\"UUU\"").is_err());
+    }
+
+    #[test]
+    fn test_solution() {
+        let cube = CubeSequenceRepr::from(&[Rotation::R, Rotation::U]);
+        let solution = KociembaSolver::new().solve_with_own_config(&cube).unwrap();
+        let json = serde_json::to_value(&solution).unwrap();
+        assert_eq!(json["moves"], "U' R'");
+        assert_eq!(json["phases"][0], solution.phases[0]);
+    }
+}
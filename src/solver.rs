@@ -0,0 +1,233 @@
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+use crate::goal::{Goal, GoalSolver};
+use crate::kociemba::{KociembaSolver, SearchStats, Solution, SolverConfig};
+use crate::optimal::OptimalSolver;
+use crate::restricted::RestrictedSolver;
+use crate::rubiks_cube::{CubeSequenceRepr, Face, ParseError};
+use crate::sequence::MoveSequence;
+use crate::thistlethwaite::ThistlethwaiteSolver;
+
+// A common interface over the solving algorithms, so callers can pick one by name
+//
+// `config.max_length` is the length a solver may stop at; the searches that
+// only ever return shortest solutions (optimal, restricted, goals) give up past it.
+// Every solver checks the cube first, as the deprecated piece fields can still
+// make one the searches would panic on.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    // no solution within config.max_length moves
+    NotFound,
+    // config.timeout ran out before any solution turned up
+    Timeout,
+    // the solver's moves can't reach this state
+    Unreachable,
+    // the pieces don't make up a real cube, see CubeSequenceRepr::validate; only
+    // possible while the piece fields can still be written directly
+    Invalid(ParseError),
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::NotFound => write!(f, "no solution within the length limit"),
+            SolveError::Timeout => write!(f, "no solution found before the timeout"),
+            SolveError::Unreachable => write!(f, "the cube can't be solved with this solver's moves"),
+            SolveError::Invalid(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+pub trait Solver: Send + Sync {
+    fn solve(&self, cube: &CubeSequenceRepr, config: &SolverConfig) -> Result<Solution, SolveError>;
+}
+
+// names accepted by `by_name`
+pub const ALGORITHMS: [&str; 6] = ["kociemba", "thistlethwaite", "optimal", "cross", "eoline", "first-block"];
+
+// the goal solvers solve only their goal (cross on D, EOLine, Roux first block), not the whole cube;
+// "optimal" builds its tables on its first solve, which takes a while (see optimal.rs)
+pub fn by_name(name: &str) -> Option<Box<dyn Solver>> {
+    Some(match name {
+        "kociemba" => Box::new(KociembaSolver::new()),
+        "thistlethwaite" => Box::new(ThistlethwaiteSolver::new()),
+        "optimal" => Box::new(OptimalSolver::new()),
+        "cross" => Box::new(GoalSolver::new(Goal::cross(Face::D))),
+        "eoline" => Box::new(GoalSolver::new(Goal::eoline())),
+        "first-block" => Box::new(GoalSolver::new(Goal::first_block())),
+        _ => return None,
+    })
+}
+
+// `config` is the one that applies, as for every other solver; the config the
+// solver was built with only goes for its inherent methods (solve_with_own_config,
+// solve_traced, solve_to). A solution longer than max_length still counts
+// if the timeout cut the search short, as the best one found
+impl Solver for KociembaSolver {
+    fn solve(&self, cube: &CubeSequenceRepr, config: &SolverConfig) -> Result<Solution, SolveError> {
+        cube.validate().map_err(SolveError::Invalid)?;
+        let start = Instant::now();
+        let solution = KociembaSolver::with_config(*config).solve_with_own_config(cube);
+        let expired = config.timeout.is_some_and(|t| start.elapsed() >= t);
+        match solution {
+            Some(solution) if solution.moves.len() <= config.max_length || expired => Ok(solution),
+            None if expired => Err(SolveError::Timeout),
+            _ => Err(SolveError::NotFound),
+        }
+    }
+}
+
+// the steps give what they give, so only the timeout applies, not max_length
+impl Solver for ThistlethwaiteSolver {
+    fn solve(&self, cube: &CubeSequenceRepr, config: &SolverConfig) -> Result<Solution, SolveError> {
+        cube.validate().map_err(SolveError::Invalid)?;
+        let mut budget = Budget::new(config.timeout);
+        let solution = self.solve_within(cube, &mut budget).ok_or(SolveError::Timeout)?;
+        Ok(Solution {
+            moves: solution.moves,
+            phases: solution.phases.iter().map(|p| p.len()).collect(),
+            nodes: budget.nodes,
+            elapsed: solution.elapsed,
+            stats: SearchStats::default(),
+        })
+    }
+}
+
+impl Solver for OptimalSolver {
+    fn solve(&self, cube: &CubeSequenceRepr, config: &SolverConfig) -> Result<Solution, SolveError> {
+        cube.validate().map_err(SolveError::Invalid)?;
+        let mut budget = Budget::new(config.timeout);
+        let moves = self.solve_within(cube, config.max_length, &mut budget);
+        budget.finish(moves)
+    }
+}
+
+impl Solver for RestrictedSolver {
+    fn solve(&self, cube: &CubeSequenceRepr, config: &SolverConfig) -> Result<Solution, SolveError> {
+        cube.validate().map_err(SolveError::Invalid)?;
+        if !self.subgroup().contains(cube) {
+            return Err(SolveError::Unreachable);
+        }
+        let mut budget = Budget::new(config.timeout);
+        let moves = self.solve_within(cube, config.max_length, &mut budget);
+        budget.finish(moves)
+    }
+}
+
+impl Solver for GoalSolver {
+    fn solve(&self, cube: &CubeSequenceRepr, config: &SolverConfig) -> Result<Solution, SolveError> {
+        cube.validate().map_err(SolveError::Invalid)?;
+        let mut budget = Budget::new(config.timeout);
+        let moves = self.solve_within(cube, config.max_length, &mut budget);
+        budget.finish(moves)
+    }
+}
+
+// node counting and deadline checks for the IDA* searches
+pub(crate) struct Budget {
+    pub nodes: u64,
+    start: Instant,
+    deadline: Option<Instant>,
+    pub expired: bool,
+}
+
+// how often (in nodes) a search looks at the clock
+pub(crate) const TIMEOUT_CHECK_INTERVAL: u64 = 4096;
+
+impl Budget {
+    pub fn new(timeout: Option<Duration>) -> Self {
+        let start = Instant::now();
+        Budget {
+            nodes: 0,
+            start,
+            deadline: timeout.map(|t| start + t),
+            expired: false,
+        }
+    }
+
+    // count a node, returns true once the search should stop
+    pub fn tick(&mut self) -> bool {
+        self.nodes += 1;
+        if self.nodes % TIMEOUT_CHECK_INTERVAL == 0 {
+            if let Some(deadline) = self.deadline {
+                if Instant::now() >= deadline {
+                    self.expired = true;
+                }
+            }
+        }
+        self.expired
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    // the outcome of a single-phase search that stopped with `found`
+    pub fn finish(&self, found: Option<MoveSequence>) -> Result<Solution, SolveError> {
+        match found {
+            Some(moves) => Ok(Solution {
+                phases: vec![moves.len()],
+                moves,
+                nodes: self.nodes,
+                elapsed: self.elapsed(),
+                stats: SearchStats::default(),
+            }),
+            None if self.expired => Err(SolveError::Timeout),
+            None => Err(SolveError::NotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rubiks_cube::Rotation::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_by_name() {
+        let cube = CubeSequenceRepr::from(&[R, U, Fr, D2]);
+        let config = SolverConfig::default();
+        for name in ["kociemba", "thistlethwaite"].iter() {
+            let solution = by_name(name).unwrap().solve(&cube, &config).unwrap();
+            assert_eq!(cube.multiply(&CubeSequenceRepr::from(solution.moves.moves())), CubeSequenceRepr::new());
+        }
+        assert_eq!(by_name("optimal").unwrap().solve(&cube, &config).unwrap().moves.len(), 4);
+        let cross = by_name("cross").unwrap().solve(&cube, &config).unwrap();
+        assert!(Goal::cross(Face::D).is_solved(&cube.multiply(&CubeSequenceRepr::from(cross.moves.moves()))));
+        assert!(by_name("nope").is_none());
+    }
+
+    #[test]
+    fn test_errors() {
+        let cube = CubeSequenceRepr::from(&[R, U, Fr, D2, L]);
+        let optimal = by_name("optimal").unwrap();
+        let short = SolverConfig { max_length: 4, timeout: None };
+        assert_eq!(optimal.solve(&cube, &short).unwrap_err(), SolveError::NotFound);
+        let none = SolverConfig { timeout: Some(Duration::ZERO), ..SolverConfig::default() };
+        let random = CubeSequenceRepr::random(&mut rand::rngs::StdRng::seed_from_u64(5));
+        assert_eq!(by_name("thistlethwaite").unwrap().solve(&random, &none).unwrap_err(), SolveError::Timeout);
+        // the config passed in applies, not the one the solver was built with
+        let stuck = KociembaSolver::with_config(none);
+        assert!(stuck.solve_with_own_config(&random).is_none());
+        assert!(stuck.solve(&random, &SolverConfig { max_length: 30, timeout: None }).is_ok());
+        assert_eq!(KociembaSolver::new().solve(&random, &none).unwrap_err(), SolveError::Timeout);
+        // with no timeout, too long is not found, whatever the search turned up
+        let one = CubeSequenceRepr::from(&[R]);
+        let zero = SolverConfig { max_length: 0, timeout: None };
+        assert_eq!(KociembaSolver::new().solve(&one, &zero).unwrap_err(), SolveError::NotFound);
+        assert_eq!(KociembaSolver::new().solve(&one, &SolverConfig { max_length: 1, timeout: None }).unwrap().moves.len(), 1);
+        let ru = RestrictedSolver::new(&[R, Rr, R2, U, Ur, U2]);
+        assert_eq!(Solver::solve(&ru, &cube, &SolverConfig::default()).unwrap_err(), SolveError::Unreachable);
+        let mut broken = cube;
+        broken.ep.0[0] = 12;
+        for name in ALGORITHMS.iter() {
+            let err = by_name(name).unwrap().solve(&broken, &short).unwrap_err();
+            assert!(matches!(err, SolveError::Invalid(ParseError::Unsolvable(_))));
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use std::time::Duration;
+
+use crate::heuristic::{phase1_medge_encode_opt, prune_move, PruneTable, ALL_MOVES, PHASE2_MOVES};
+use crate::rubiks_cube::{co_encode, cp_encode, eo_encode, CornerOrient, CornerPerm, CubeRepr, CubeSequenceRepr, EdgeOrient, EdgePerm, Rotation};
+use crate::sequence::MoveSequence;
+use crate::solver::Budget;
+use crate::util::{encode_comb, encode_perm};
+
+// Thistlethwaite's algorithm
+//
+// Solves through the chain of subgroups
+//   G0 = <U, D, R, L, F, B>
+//   G1 = <U, D, R2, L2, F, B>       edges oriented
+//   G2 = <U, D, R2, L2, F2, B2>     corners oriented, E-slice edges in the E slice
+//   G3 = <U2, D2, R2, L2, F2, B2>   corners in their tetrads, M and S slice edges in their slices
+//   G4 = {solved}
+// each step using only the moves of the group it starts in. Edge orientation
+// here is flipped by quarter turns of R and L, so G1 keeps F and B instead of
+// the R and L of the textbook description.
+//
+// The first three steps have a table of exact distances to the next group;
+// the last one takes an IDA* search over separate corner and edge tables.
+
+pub const G0_MOVES: [Rotation; 18] = ALL_MOVES;
+
+pub const G1_MOVES: [Rotation; 14] = [
+    Rotation::U, Rotation::Ur, Rotation::U2,
+    Rotation::D, Rotation::Dr, Rotation::D2,
+    Rotation::F, Rotation::Fr, Rotation::F2,
+    Rotation::B, Rotation::Br, Rotation::B2,
+    Rotation::R2, Rotation::L2,
+];
+
+pub const G2_MOVES: [Rotation; 10] = PHASE2_MOVES;
+
+pub const G3_MOVES: [Rotation; 6] = [
+    Rotation::U2, Rotation::D2, Rotation::R2, Rotation::L2, Rotation::F2, Rotation::B2
+];
+
+// M slice edges (UB, UF, DB, DF) and S slice edges (UR, UL, DR, DL)
+const M_SLICE: [u8; 4] = [0, 2, 8, 10];
+const S_SLICE: [u8; 4] = [1, 3, 9, 11];
+const E_SLICE: [u8; 4] = [4, 5, 6, 7];
+// the edge positions outside the E slice, which G2 moves keep to themselves
+const UD_EDGES: [usize; 8] = [0, 1, 2, 3, 8, 9, 10, 11];
+
+// two parts of the cube tracked together; public only as the piece type of
+// the G1 and G2 tables, see g1_table
+#[doc(hidden)]
+#[derive(Copy, Clone)]
+pub struct Pair<A, B>(A, B);
+
+impl<A: CubeRepr, B: CubeRepr> CubeRepr for Pair<A, B> {
+    fn rotate(&mut self, r: Rotation) {
+        self.0.rotate(r);
+        self.1.rotate(r);
+    }
+}
+
+// G1 -> G2: corner orientation and where the E slice edges are
+fn g1_encode(repr: &Pair<CornerOrient, EdgePerm>) -> usize {
+    co_encode(&repr.0) * 495 + phase1_medge_encode_opt(&repr.1)
+}
+
+// G2 -> G3: the corner permutation and which of the 8 U and D layer positions hold M slice edges
+fn g2_encode(repr: &Pair<CornerPerm, EdgePerm>) -> usize {
+    let mut buf = [0usize; 4];
+    let mut j = 0;
+    for (k, &pos) in UD_EDGES.iter().enumerate() {
+        if M_SLICE.contains(&repr.1 .0[pos]) {
+            buf[j] = k;
+            j += 1;
+        }
+    }
+    cp_encode(&repr.0) * 70 + encode_comb(&buf, 8)
+}
+
+// G3 -> G4: the permutation within each slice
+fn g3_edge_encode(repr: &EdgePerm) -> usize {
+    [M_SLICE, S_SLICE, E_SLICE].iter().fold(0, |acc, slice| {
+        let mut perm = [0u8; 4];
+        for (k, &pos) in slice.iter().enumerate() {
+            perm[k] = slice.iter().position(|&e| e == repr.0[pos as usize]).unwrap_or(0) as u8;
+        }
+        acc * 24 + encode_perm(&perm)
+    })
+}
+
+// how each table below is built, public so the benchmarks in benches/ can time
+// exactly that
+pub fn g0_table() -> PruneTable<EdgeOrient> {
+    let mut pt = PruneTable::new(2048);
+    pt.init(eo_encode, &[EdgeOrient::new()], &G0_MOVES);
+    pt
+}
+
+pub fn g1_table() -> PruneTable<Pair<CornerOrient, EdgePerm>> {
+    let mut pt = PruneTable::new(2187 * 495);
+    pt.init(g1_encode, &[Pair(CornerOrient::new(), EdgePerm::new())], &G1_MOVES);
+    pt
+}
+
+pub fn g2_table() -> PruneTable<Pair<CornerPerm, EdgePerm>> {
+    // G3 holds 96 corner permutations, any of them will do
+    let mut corners = vec![CornerPerm::new()];
+    let mut i = 0;
+    while i < corners.len() {
+        for &r in G3_MOVES.iter() {
+            let mut cp = corners[i];
+            cp.rotate(r);
+            if !corners.contains(&cp) {
+                corners.push(cp);
+            }
+        }
+        i += 1;
+    }
+    let goals: Vec<_> = corners.into_iter().map(|cp| Pair(cp, EdgePerm::new())).collect();
+    let mut pt = PruneTable::new(40320 * 70);
+    pt.init(g2_encode, &goals, &G2_MOVES);
+    pt
+}
+
+pub fn g3_corner_table() -> PruneTable<CornerPerm> {
+    let mut pt = PruneTable::new(40320);
+    pt.init(cp_encode, &[CornerPerm::new()], &G3_MOVES);
+    pt
+}
+
+pub fn g3_edge_table() -> PruneTable<EdgePerm> {
+    let mut pt = PruneTable::new(24 * 24 * 24);
+    pt.init(g3_edge_encode, &[EdgePerm::new()], &G3_MOVES);
+    pt
+}
+
+lazy_static! {
+    static ref G0_PT: PruneTable<EdgeOrient> = g0_table();
+    static ref G1_PT: PruneTable<Pair<CornerOrient, EdgePerm>> = g1_table();
+    static ref G2_PT: PruneTable<Pair<CornerPerm, EdgePerm>> = g2_table();
+    static ref G3_CORNER_PT: PruneTable<CornerPerm> = g3_corner_table();
+    static ref G3_EDGE_PT: PruneTable<EdgePerm> = g3_edge_table();
+}
+
+#[derive(Debug, Clone)]
+pub struct ThistlethwaiteSolution {
+    pub moves: MoveSequence,
+    // the moves of each step, G0 -> G1 first
+    pub phases: [MoveSequence; 4],
+    pub elapsed: Duration,
+}
+
+pub struct ThistlethwaiteSolver {}
+
+impl Default for ThistlethwaiteSolver {
+    fn default() -> Self {
+        ThistlethwaiteSolver::new()
+    }
+}
+
+impl ThistlethwaiteSolver {
+    pub fn new() -> Self {
+        ThistlethwaiteSolver {}
+    }
+
+    // build all tables up front instead of on first use
+    pub fn init_tables() {
+        lazy_static::initialize(&G0_PT);
+        lazy_static::initialize(&G1_PT);
+        lazy_static::initialize(&G2_PT);
+        lazy_static::initialize(&G3_CORNER_PT);
+        lazy_static::initialize(&G3_EDGE_PT);
+    }
+
+    // always finds a solution, of at most 7 + 10 + 13 + 15 moves
+    pub fn solve(&self, scrambled: &CubeSequenceRepr) -> ThistlethwaiteSolution {
+        // nothing to run out without a deadline
+        self.solve_within(scrambled, &mut Budget::new(None)).unwrap()
+    }
+
+    // the same, but None once `budget` runs out; only the last step searches,
+    // the others just walk down their tables
+    pub(crate) fn solve_within(&self, scrambled: &CubeSequenceRepr, budget: &mut Budget) -> Option<ThistlethwaiteSolution> {
+        let mut cube = *scrambled;
+        let p1 = descend(&mut cube, &G0_MOVES, |c| G0_PT[eo_encode(&c.eo)]);
+        let p2 = descend(&mut cube, &G1_MOVES, |c| G1_PT[g1_encode(&Pair(c.co, c.ep))]);
+        let p3 = descend(&mut cube, &G2_MOVES, |c| G2_PT[g2_encode(&Pair(c.cp, c.ep))]);
+        let p4 = solve_g3(&mut cube, budget)?;
+        let mut all = p1.moves().to_vec();
+        for p in [&p2, &p3, &p4].iter() {
+            all.extend(p.moves());
+        }
+        Some(ThistlethwaiteSolution {
+            moves: MoveSequence::from(all).simplify(),
+            phases: [p1, p2, p3, p4],
+            elapsed: budget.elapsed(),
+        })
+    }
+}
+
+// with exact distances every step just has to find a move that gets one closer
+fn descend<F>(cube: &mut CubeSequenceRepr, moves: &[Rotation], dist: F) -> MoveSequence
+    where F: Fn(&CubeSequenceRepr) -> i8 {
+    let mut res = MoveSequence::new();
+    let mut d = dist(cube);
+    while d > 0 {
+        // no pruning of moves here: after D the way down may well start with U
+        let (next, r) = moves.iter()
+            .map(|&r| {
+                let mut next = *cube;
+                next.rotate(r);
+                (next, r)
+            })
+            .find(|(next, _)| dist(next) == d - 1)
+            .unwrap();
+        res.push(r);
+        *cube = next;
+        d -= 1;
+    }
+    res
+}
+
+fn h3(cube: &CubeSequenceRepr) -> i8 {
+    G3_CORNER_PT[cp_encode(&cube.cp)].max(G3_EDGE_PT[g3_edge_encode(&cube.ep)])
+}
+
+fn solve_g3(cube: &mut CubeSequenceRepr, budget: &mut Budget) -> Option<MoveSequence> {
+    let mut moves = vec![];
+    for depth in h3(cube).. {
+        if search_g3(*cube, depth, &mut moves, budget) {
+            break;
+        }
+        if budget.expired {
+            return None;
+        }
+    }
+    for &r in moves.iter() {
+        cube.rotate(r);
+    }
+    Some(MoveSequence::from(moves))
+}
+
+fn search_g3(cube: CubeSequenceRepr, depth: i8, moves: &mut Vec<Rotation>, budget: &mut Budget) -> bool {
+    if budget.tick() {
+        return false;
+    }
+    if cube == CubeSequenceRepr::new() {
+        return true;
+    }
+    for &r in G3_MOVES.iter() {
+        if moves.last().is_some_and(|&prev| prune_move(prev, r)) {
+            continue;
+        }
+        let mut next = cube;
+        next.rotate(r);
+        if h3(&next) < depth {
+            moves.push(r);
+            if search_g3(next, depth - 1, moves, budget) {
+                return true;
+            }
+            moves.pop();
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::kociemba::KociembaSolver;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_solve() {
+        let solver = ThistlethwaiteSolver::new();
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..5 {
+            let cube = CubeSequenceRepr::random(&mut rng);
+            let solution = solver.solve(&cube);
+            assert_eq!(cube.multiply(&CubeSequenceRepr::from(solution.moves.moves())), CubeSequenceRepr::new());
+            // each step stays inside the group it starts from
+            let groups: [&[Rotation]; 4] = [&G0_MOVES, &G1_MOVES, &G2_MOVES, &G3_MOVES];
+            for (phase, group) in solution.phases.iter().zip(groups.iter()) {
+                assert!(phase.moves().iter().all(|m| group.contains(m)));
+            }
+            assert!(solution.moves.len() <= 45);
+        }
+    }
+
+    #[test]
+    fn test_agrees_with_kociemba() {
+        let cube = CubeSequenceRepr::from(&[Rotation::R, Rotation::U, Rotation::F2, Rotation::Lr, Rotation::D, Rotation::B]);
+        let thistlethwaite = ThistlethwaiteSolver::new().solve(&cube);
+        let kociemba = KociembaSolver::new().solve_with_own_config(&cube).unwrap();
+        assert!(kociemba.moves.len() <= thistlethwaite.moves.len());
+        for moves in [thistlethwaite.moves, kociemba.moves].iter() {
+            assert_eq!(cube.multiply(&CubeSequenceRepr::from(moves.moves())), CubeSequenceRepr::new());
+        }
+    }
+}
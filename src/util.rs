@@ -1,18 +1,22 @@
 use num_traits::ToPrimitive;
 
+// Permutation and combination ranking. The module is public for this release
+// only; outside the crate it's rubiks_cube::try_encode_perm
+
 const MAX_PERM: usize = 12;
 const FACTORIALS: [usize; MAX_PERM + 1] = [
     1, 1, 2, 6, 24, 120, 720, 5040, 40320, 362880, 3628800, 39916800, 479001600
 ];
 
+#[deprecated(note = "use `rubiks_cube::try_encode_perm`")]
 #[inline(always)]
 pub fn encode_perm<T: Copy + ToPrimitive>(arr: &[T]) -> usize {
     // TODO better array initialization?
     let mut mapped = [0u8; MAX_PERM];
     let mut index = [0u8; MAX_PERM];
     for i in 0u8..(arr.len() as u8) {
-        mapped[i as usize] = i as u8;
-        index[i as usize] = i as u8;
+        mapped[i as usize] = i;
+        index[i as usize] = i;
     }
     let mut res: usize = 0;
     for i in (0..arr.len()).rev() {
@@ -26,6 +30,23 @@ pub fn encode_perm<T: Copy + ToPrimitive>(arr: &[T]) -> usize {
     res
 }
 
+// encode_perm for untrusted input: None unless `arr` is a permutation of 0..arr.len()
+pub fn try_encode_perm<T: Copy + ToPrimitive>(arr: &[T]) -> Option<usize> {
+    if arr.len() > MAX_PERM {
+        return None;
+    }
+    let mut seen = [false; MAX_PERM];
+    for x in arr.iter() {
+        let v = x.to_usize()?;
+        if v >= arr.len() || seen[v] {
+            return None;
+        }
+        seen[v] = true;
+    }
+    Some(encode_perm(arr))
+}
+
+#[deprecated(note = "internal, to become private")]
 #[inline(always)]
 pub fn encode_comb<T: Copy + ToPrimitive>(arr: &[T], n: usize) -> usize {
     let mut occupied = [false; MAX_PERM];
@@ -50,6 +71,7 @@ pub fn encode_comb<T: Copy + ToPrimitive>(arr: &[T], n: usize) -> usize {
 const COMB: [[usize; 4]; 13] =
     [[1, 0, 0, 0], [1, 1, 0, 0], [1, 2, 1, 0], [1, 3, 3, 1], [1, 4, 6, 4], [1, 5, 10, 10], [1, 6, 15, 20], [1, 7, 21, 35], [1, 8, 28, 56], [1, 9, 36, 84], [1, 10, 45, 120], [1, 11, 55, 165], [1, 12, 66, 220]];
 
+#[deprecated(note = "internal, to become private")]
 #[inline(always)]
 pub fn comb(n: usize, k: usize) -> usize {
     COMB[n][k]
@@ -68,4 +90,13 @@ mod test {
         assert_eq!(encode_comb(&[0, 1, 2, 3], 12), 494);
     }
 
+    #[test]
+    fn test_try_encode() {
+        assert_eq!(try_encode_perm(&[2u8, 0, 1]), Some(encode_perm(&[2u8, 0, 1])));
+        assert_eq!(try_encode_perm(&[0u8, 0, 1]), None);
+        assert_eq!(try_encode_perm(&[0u8, 3, 1]), None);
+        assert_eq!(try_encode_perm(&[0u8; 13]), None);
+        assert_eq!(try_encode_perm(&[-1i32]), None);
+    }
+
 }
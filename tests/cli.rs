@@ -0,0 +1,82 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+// The command line binary end to end: arguments, input errors and exit codes
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cube-solver"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+const SOLVED: &str = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+#[test]
+fn test_facelet_errors() {
+    let short = run(&["solve", "-f", "UUU"], "");
+    assert_eq!(short.status.code(), Some(2));
+    assert!(stderr(&short).contains("expected 54 facelets"), "{}", stderr(&short));
+    let centre = SOLVED.replacen("UUUUU", "UUUUR", 1).replacen("RRRRR", "RRRRU", 1);
+    let output = run(&["solve", "-f", &centre], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("wrong centre"), "{}", stderr(&output));
+    // the URF corner twisted in place: the right stickers, in an unreachable state
+    let mut twisted: Vec<char> = SOLVED.chars().collect();
+    twisted[8] = 'F';
+    twisted[9] = 'U';
+    twisted[20] = 'R';
+    let twisted: String = twisted.into_iter().collect();
+    let output = run(&["show", "--color", "never", "-f", &twisted], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("twisted corner"), "{}", stderr(&output));
+}
+
+#[test]
+fn test_stdin_lines() {
+    // the bad line is reported and the ones either side still solved
+    let output = run(&["solve", "-a", "thistlethwaite"], "R U\nR X\n\nF2\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output).lines().count(), 2);
+    assert!(stderr(&output).contains("line 2"), "{}", stderr(&output));
+    let output = run(&["show", "--color", "never"], &format!("{}\n", SOLVED));
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains(SOLVED));
+}
+
+#[test]
+fn test_usage() {
+    assert_eq!(run(&[], "").status.code(), Some(2));
+    assert_eq!(run(&["frobnicate"], "").status.code(), Some(2));
+    assert_eq!(run(&["solve", "--nope"], "").status.code(), Some(2));
+    assert_eq!(run(&["solve", "-a", "nope", "R"], "").status.code(), Some(2));
+    let help = run(&["--help"], "");
+    assert_eq!(help.status.code(), Some(0));
+    assert!(stdout(&help).starts_with("Usage:"));
+    assert_eq!(run(&["verify", "R U", "U' R'"], "").status.code(), Some(0));
+    assert_eq!(run(&["verify", "R U", "R' U'"], "").status.code(), Some(1));
+}
+
+#[test]
+fn test_failures() {
+    // valid input that the solver gives up on is a failure, not a usage error
+    let scramble = run(&["scramble", "--seed", "5", "--timeout", "0"], "");
+    assert_eq!(scramble.status.code(), Some(1));
+    assert!(stderr(&scramble).starts_with("error: "));
+    let solve = run(&["solve", "-a", "cross", "-l", "0", "R"], "");
+    assert_eq!(solve.status.code(), Some(1));
+    assert_eq!(stderr(&solve).trim_end(), "error: no solution within the length limit");
+}
@@ -0,0 +1,40 @@
+use std::env;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+// The C example in examples/c, compiled against include/cube_solver.h and linked
+// with the cdylib cargo builds next to the binaries, so a header that no longer
+// matches the Rust side fails here; `make -C examples/c test` does the same by hand
+
+#[test]
+fn test_c_example() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // target/<profile>, where the cdylib is too
+    let lib_dir = Path::new(env!("CARGO_BIN_EXE_cube-solver")).parent().unwrap();
+    let exe = Path::new(env!("CARGO_TARGET_TMPDIR")).join("solve_test");
+    let cc = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let compiled = Command::new(&cc)
+        .args(["-Wall", "-Wextra", "-Werror", "-std=c99"])
+        .arg("-I").arg(root.join("include"))
+        .arg("-o").arg(&exe)
+        .arg(root.join("examples/c/solve_test.c"))
+        .arg("-L").arg(lib_dir)
+        .arg("-lcube_solver")
+        .status();
+    match compiled {
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            eprintln!("no C compiler ({}), skipping", cc);
+            return;
+        }
+        compiled => assert!(compiled.unwrap().success(), "solve_test.c didn't compile"),
+    }
+    let output = Command::new(&exe)
+        .env("LD_LIBRARY_PATH", lib_dir)
+        .env("DYLD_LIBRARY_PATH", lib_dir)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}", stdout);
+    assert!(!stdout.contains("FAIL"), "{}", stdout);
+}
@@ -0,0 +1,192 @@
+use lazy_static::lazy_static;
+use proptest::prelude::*;
+
+use cube_solver::big_cube::BigCube;
+use cube_solver::goal::{Goal, GoalSolver};
+use cube_solver::heuristic::ALL_MOVES;
+use cube_solver::kociemba::{KociembaSolver, SolverConfig};
+use cube_solver::optimal::OptimalSolver;
+use cube_solver::pocket::{PocketCube, PocketSolver};
+use cube_solver::restricted::RestrictedSolver;
+use cube_solver::rubiks_cube::{ColoredCube, CubeRepr, CubeSequenceRepr, Face, Rotation};
+use cube_solver::sequence::MoveSequence;
+use cube_solver::solver::Solver;
+use cube_solver::thistlethwaite::{ThistlethwaiteSolver, G0_MOVES, G1_MOVES, G2_MOVES, G3_MOVES};
+
+// Properties of the cube model over random move sequences
+
+lazy_static! {
+    static ref OPTIMAL: OptimalSolver = OptimalSolver::new();
+    static ref ALL: RestrictedSolver = RestrictedSolver::new(&ALL_MOVES);
+    static ref POCKET: PocketSolver = PocketSolver::new();
+    static ref GOALS: Vec<GoalSolver> = vec![
+        GoalSolver::new(Goal::cross(Face::D)),
+        GoalSolver::new(Goal::eoline()),
+        GoalSolver::new(Goal::first_block()),
+    ];
+}
+
+fn rotation() -> impl Strategy<Value = Rotation> {
+    prop::sample::select(ALL_MOVES.to_vec())
+}
+
+fn moves(max: usize) -> impl Strategy<Value = Vec<Rotation>> {
+    prop::collection::vec(rotation(), 0..=max)
+}
+
+fn solves(cube: &CubeSequenceRepr, solution: &MoveSequence) -> bool {
+    cube.multiply(&CubeSequenceRepr::from(solution.moves())) == CubeSequenceRepr::new()
+}
+
+// the facelets after `seq` by turning stickers in space, on BigCube, which knows
+// nothing of pieces or orientations
+fn sticker_facelets(seq: &[Rotation]) -> String {
+    let mut cube = BigCube::<3>::new();
+    for &r in seq.iter() {
+        cube.rotate(r);
+    }
+    [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B].iter()
+        .flat_map(|&f| cube.faces[f as usize].iter().flatten().map(|c| c.to_char()).collect::<Vec<_>>())
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn rotation_kinds(r in rotation()) {
+        // every move is exactly one of clockwise, counter-clockwise or a half turn
+        let kinds = [r.is_cw(), r.is_ccw(), r.is_180()];
+        prop_assert_eq!(kinds.iter().filter(|&&k| k).count(), 1);
+        prop_assert_eq!(Rotation::from_quarter_turns(r, r.quarter_turns()), Some(r));
+        prop_assert_eq!(r.reverse().reverse(), r);
+        prop_assert_eq!(r.reverse().quarter_turns(), 4 - r.quarter_turns());
+    }
+
+    #[test]
+    fn reverse_undoes(seq in moves(40)) {
+        let mut cube = CubeSequenceRepr::from(seq.as_slice());
+        for r in seq.iter().rev() {
+            cube.rotate(r.reverse());
+        }
+        prop_assert_eq!(cube, CubeSequenceRepr::new());
+    }
+
+    #[test]
+    fn inverse(seq in moves(40)) {
+        let cube = CubeSequenceRepr::from(seq.as_slice());
+        prop_assert_eq!(cube.multiply(&cube.inverse()), CubeSequenceRepr::new());
+        prop_assert_eq!(cube.inverse().multiply(&cube), CubeSequenceRepr::new());
+        let inverse = MoveSequence::from(seq.clone()).inverse();
+        prop_assert_eq!(CubeSequenceRepr::from(inverse.moves()), cube.inverse());
+    }
+
+    #[test]
+    fn multiply_is_concatenation(a in moves(20), b in moves(20)) {
+        let both: Vec<_> = a.iter().chain(b.iter()).cloned().collect();
+        prop_assert_eq!(
+            CubeSequenceRepr::from(a.as_slice()).multiply(&CubeSequenceRepr::from(b.as_slice())),
+            CubeSequenceRepr::from(both.as_slice())
+        );
+    }
+
+    #[test]
+    fn orientation_sums(seq in moves(40)) {
+        // the piece model's move tables against turning the stickers themselves:
+        // read back as pieces, the stickers give the same cube
+        let cube = CubeSequenceRepr::from(seq.as_slice());
+        let turned = CubeSequenceRepr::from_facelets(&sticker_facelets(&seq)).unwrap();
+        prop_assert_eq!(turned, cube);
+        prop_assert_eq!(turned.co().0.iter().map(|&t| t as u32).sum::<u32>() % 3, 0);
+        prop_assert_eq!(turned.eo().0.iter().filter(|&&f| f).count() % 2, 0);
+    }
+
+    #[test]
+    fn stickers(seq in moves(40)) {
+        let cube = CubeSequenceRepr::from(seq.as_slice());
+        let mut vis = ColoredCube::new();
+        cube.visualize(&mut vis);
+        let mut counts = [0; 6];
+        for face in vis.dat.iter() {
+            for row in face.iter() {
+                for &f in row.iter() {
+                    counts[f as usize] += 1;
+                }
+            }
+        }
+        prop_assert_eq!(counts, [9; 6]);
+        // and the stickers say exactly where every piece is and how it's turned
+        prop_assert_eq!(CubeSequenceRepr::from_facelets(&cube.to_facelets()).unwrap(), cube);
+    }
+
+    #[test]
+    fn simplify_keeps_state(seq in moves(40)) {
+        let seq = MoveSequence::from(seq);
+        let simple = seq.simplify();
+        prop_assert!(simple.len() <= seq.len());
+        prop_assert_eq!(CubeSequenceRepr::from(simple.moves()), CubeSequenceRepr::from(seq.moves()));
+        let parsed: MoveSequence = simple.to_string().parse().unwrap();
+        prop_assert_eq!(parsed.moves(), simple.moves());
+    }
+}
+
+proptest! {
+    // solves are slow next to the model checks, so fewer cases
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn kociemba_solves(seed in any::<u64>()) {
+        use rand::SeedableRng;
+        let cube = CubeSequenceRepr::random(&mut rand::rngs::StdRng::seed_from_u64(seed));
+        let solver = KociembaSolver::with_config(SolverConfig { max_length: 30, timeout: None });
+        let solution = solver.solve_with_own_config(&cube).unwrap();
+        prop_assert!(solves(&cube, &solution.moves));
+    }
+
+    #[test]
+    fn thistlethwaite_solves(seed in any::<u64>()) {
+        use rand::SeedableRng;
+        let cube = CubeSequenceRepr::random(&mut rand::rngs::StdRng::seed_from_u64(seed));
+        let solution = ThistlethwaiteSolver::new().solve(&cube);
+        prop_assert!(solves(&cube, &solution.moves));
+        // each step keeps to the moves of the group it starts in, and the steps
+        // together are the solution
+        let groups: [&[Rotation]; 4] = [&G0_MOVES, &G1_MOVES, &G2_MOVES, &G3_MOVES];
+        for (phase, group) in solution.phases.iter().zip(groups.iter()) {
+            prop_assert!(phase.moves().iter().all(|m| group.contains(m)));
+        }
+        let steps: Vec<Rotation> = solution.phases.iter().flat_map(|p| p.moves().to_vec()).collect();
+        prop_assert!(solves(&cube, &MoveSequence::from(steps)));
+        prop_assert!(solution.moves.len() <= 45);
+    }
+
+    #[test]
+    fn goals_solve(seq in moves(30)) {
+        let cube = CubeSequenceRepr::from(seq.as_slice());
+        for solver in GOALS.iter() {
+            let solution = solver.solve(&cube, 12).unwrap();
+            prop_assert!(solver.goal().is_solved(&cube.multiply(&CubeSequenceRepr::from(solution.moves()))));
+            // shortest, so never longer than undoing the scramble
+            prop_assert!(solution.len() <= MoveSequence::from(seq.clone()).simplify().len());
+        }
+    }
+
+    #[test]
+    fn optimal_solves(seq in moves(5)) {
+        let cube = CubeSequenceRepr::from(seq.as_slice());
+        let config = SolverConfig { max_length: seq.len(), timeout: None };
+        let solution = OPTIMAL.solve(&cube, &config).unwrap().moves;
+        prop_assert!(solves(&cube, &solution));
+        prop_assert!(solution.len() <= MoveSequence::from(seq.clone()).simplify().len());
+        // as short as brute force over all 18 moves finds
+        prop_assert_eq!(solution.len(), ALL.solve(&cube, seq.len()).unwrap().len());
+    }
+
+    #[test]
+    fn pocket_solves(seq in moves(30)) {
+        let cube = CubeSequenceRepr::from(seq.as_slice());
+        let solution = POCKET.solve_cube(&cube);
+        let solved = cube.multiply(&CubeSequenceRepr::from(solution.moves()));
+        // the corners end up solved relative to each other, whichever way the cube is held
+        prop_assert!(PocketCube::from_cube(&solved).is_solved());
+        prop_assert!(solution.len() <= 11);
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+
+use cube_solver::heuristic::{prune_move, ALL_MOVES};
+use cube_solver::kociemba::{phase1_bound, phase2_bound, KociembaSolver, SolverConfig};
+use cube_solver::optimal::{lower_bound, OptimalSolver};
+use cube_solver::rubiks_cube::{CornerOrient, CornerPerm, CubeRepr, CubeSequenceRepr, EdgeOrient, EdgePerm, Rotation};
+use cube_solver::sequence::MoveSequence;
+use cube_solver::solver::{SolveError, Solver};
+
+// Solvers against tests/data/optimal.txt, a corpus of states with known distances
+
+// deepest states the optimal solver gets through quickly; the rest (19 and 20)
+// take minutes to hours each, so they only run with --ignored. And the radius
+// of the table-free search, which checks distances up to twice that
+const OPTIMAL_DEPTH: usize = 10;
+const BALL_RADIUS: usize = 5;
+
+struct Entry {
+    distance: usize,
+    moves: MoveSequence,
+    name: Option<String>,
+}
+
+lazy_static! {
+    static ref CORPUS: Vec<Entry> = include_str!("data/optimal.txt").lines().filter_map(|line| {
+        let (state, name) = match line.split_once('#') {
+            Some((state, name)) => (state.trim(), Some(name.trim().to_string())),
+            None => (line.trim(), None),
+        };
+        let (distance, moves) = state.split_once(' ')?;
+        Some(Entry {
+            distance: distance.parse().unwrap(),
+            moves: moves.parse().unwrap(),
+            name: name.filter(|n| !n.is_empty()),
+        })
+    }).collect();
+
+    // every state within BALL_RADIUS moves of solved, with its distance
+    static ref BALL: HashMap<CubeSequenceRepr, usize> = {
+        let mut res = HashMap::new();
+        res.insert(CubeSequenceRepr::new(), 0);
+        let mut frontier = vec![CubeSequenceRepr::new()];
+        for d in 1..=BALL_RADIUS {
+            let mut next = vec![];
+            for cube in frontier.iter() {
+                for &r in ALL_MOVES.iter() {
+                    let mut c = *cube;
+                    c.rotate(r);
+                    res.entry(c).or_insert_with(|| {
+                        next.push(c);
+                        d
+                    });
+                }
+            }
+            frontier = next;
+        }
+        res
+    };
+}
+
+impl Entry {
+    fn cube(&self) -> CubeSequenceRepr {
+        CubeSequenceRepr::from(self.moves.moves())
+    }
+}
+
+// exact distance for anything within 2 * BALL_RADIUS moves, by trying every
+// sequence of up to BALL_RADIUS moves and looking the result up in BALL
+fn meet_in_the_middle(cube: &CubeSequenceRepr) -> Option<usize> {
+    fn walk(cube: CubeSequenceRepr, depth: usize, last: Option<Rotation>, best: &mut Option<usize>) {
+        if let Some(&d) = BALL.get(&cube) {
+            *best = Some(best.map_or(depth + d, |b| b.min(depth + d)));
+        }
+        if depth == BALL_RADIUS {
+            return;
+        }
+        for &r in ALL_MOVES.iter() {
+            if last.is_some_and(|prev| prune_move(prev, r)) {
+                continue;
+            }
+            let mut c = cube;
+            c.rotate(r);
+            walk(c, depth + 1, Some(r), best);
+        }
+    }
+    let mut best = None;
+    walk(*cube, 0, None, &mut best);
+    best
+}
+
+fn solves(cube: &CubeSequenceRepr, solution: &MoveSequence) -> bool {
+    cube.multiply(&CubeSequenceRepr::from(solution.moves())) == CubeSequenceRepr::new()
+}
+
+#[test]
+fn test_corpus() {
+    assert!(CORPUS.len() > 20);
+    let superflip = CORPUS.iter().find(|e| e.name.as_deref() == Some("superflip")).unwrap();
+    let flipped = CubeSequenceRepr::from_parts(EdgePerm::new(), CornerPerm::new(), EdgeOrient([true; 12]), CornerOrient::new()).unwrap();
+    assert_eq!(superflip.cube(), flipped);
+    assert_eq!(superflip.distance, 20);
+    let named = |name: &str| CORPUS.iter().find(|e| e.name.as_deref() == Some(name)).unwrap().cube();
+    for e in CORPUS.iter() {
+        // a sequence reaching the state is never shorter than the distance
+        assert!(e.moves.len() >= e.distance, "{}", e.moves);
+        // "a + b" is state a followed by state b
+        if let Some((first, then)) = e.name.as_deref().and_then(|n| n.split_once(" + ")) {
+            assert_eq!(e.cube(), named(first).multiply(&named(then)), "{}", e.moves);
+        }
+    }
+}
+
+#[test]
+fn test_meet_in_the_middle() {
+    // number of states at each distance, from the known distribution
+    let mut counts = [0; BALL_RADIUS + 1];
+    for &d in BALL.values() {
+        counts[d] += 1;
+    }
+    assert_eq!(counts, [1, 18, 243, 3240, 43239, 574908]);
+    for e in CORPUS.iter().filter(|e| e.distance <= 2 * BALL_RADIUS) {
+        assert_eq!(meet_in_the_middle(&e.cube()), Some(e.distance), "{}", e.moves);
+    }
+}
+
+// the solver only returns a solution once it has ruled out every shorter one,
+// so a solution exactly e.distance long confirms the distance
+#[test]
+fn test_optimal() {
+    let solver = OptimalSolver::new();
+    for e in CORPUS.iter().filter(|e| e.distance <= OPTIMAL_DEPTH) {
+        let cube = e.cube();
+        assert!(lower_bound(&cube) <= e.distance, "{}", e.moves);
+        let config = SolverConfig { max_length: e.distance, timeout: None };
+        let solution = solver.solve(&cube, &config).unwrap_or_else(|err| panic!("{} for {}", err, e.moves)).moves;
+        assert!(solves(&cube, &solution), "{}", e.moves);
+        assert_eq!(solution.len(), e.distance, "{}", e.moves);
+    }
+}
+
+// For the deepest states the last round of the search, the one that finds a
+// solution, can take hours by itself; their sequences in the corpus are as long
+// as the distance, so it's enough that the solver rules out anything shorter.
+// That still takes a couple of hours in release mode:
+// cargo test --release --test reference -- --ignored
+#[test]
+#[ignore]
+fn test_optimal_slow() {
+    let solver = OptimalSolver::new();
+    for e in CORPUS.iter().filter(|e| e.distance > OPTIMAL_DEPTH) {
+        let cube = e.cube();
+        assert_eq!(e.moves.len(), e.distance, "{}", e.moves);
+        assert!(lower_bound(&cube) <= e.distance, "{}", e.moves);
+        let config = SolverConfig { max_length: e.distance - 1, timeout: None };
+        assert_eq!(solver.solve(&cube, &config).unwrap_err(), SolveError::NotFound, "{}", e.moves);
+    }
+}
+
+#[test]
+fn test_kociemba_bounds() {
+    // a length cap Kociemba reaches quickly even for distance 20 states
+    let solver = KociembaSolver::with_config(SolverConfig { max_length: 22, timeout: None });
+    for e in CORPUS.iter() {
+        let cube = e.cube();
+        let solution = solver.solve_with_own_config(&cube).unwrap();
+        assert!(solves(&cube, &solution.moves), "{}", e.moves);
+        let len = solution.moves.len();
+        assert!(e.distance <= len && len <= 22, "{}: {} moves", e.moves, len);
+    }
+}
+
+#[test]
+fn test_kociemba_heuristics() {
+    // both phases' bounds have to be admissible, or the search can miss the
+    // shortest solution. Solving passes through G1, so phase 1's can't exceed
+    // the distance; phase 2's only has to stay under the distance in G1's
+    // moves, which is never less, so comparing it with the distance in all 18
+    // is the stricter check
+    let mut in_g1 = 0;
+    for e in CORPUS.iter() {
+        let cube = e.cube();
+        assert!(phase1_bound(&cube) <= e.distance, "{}", e.moves);
+        if let Some(h) = phase2_bound(&cube) {
+            assert!(h <= e.distance, "{}", e.moves);
+            in_g1 += 1;
+        }
+    }
+    assert!(in_g1 >= 3);
+}